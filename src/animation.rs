@@ -0,0 +1,101 @@
+use crate::error::RaytracerError;
+use crate::{Camera, World};
+
+use std::path::{Path, PathBuf};
+
+pub struct Animation<F>
+where
+    F: FnMut(usize) -> (Camera, World),
+{
+    pub frames: usize,
+    scene: F,
+}
+
+impl<F> Animation<F>
+where
+    F: FnMut(usize) -> (Camera, World),
+{
+    #[must_use]
+    pub fn new(frames: usize, scene: F) -> Self {
+        Self { frames, scene }
+    }
+
+    pub fn render(
+        &mut self,
+        dir: &Path,
+        mut progress: Option<&mut dyn FnMut(usize, usize)>,
+    ) -> Result<(), RaytracerError> {
+        for frame in 0..self.frames {
+            let (camera, world) = (self.scene)(frame);
+            camera.render(&world).try_save(&frame_path(dir, frame))?;
+
+            if let Some(progress) = progress.as_mut() {
+                progress(frame + 1, self.frames);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn frame_path(dir: &Path, frame: usize) -> PathBuf {
+    dir.join(format!("frame_{frame:04}.ppm"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::test_world::test_world;
+    use crate::{vector, Matrix, Point};
+
+    use std::f64::consts::PI;
+    use std::fs;
+
+    #[test]
+    fn render_frames() {
+        let dir = std::env::temp_dir().join(format!("raytracer_animation_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut animation = Animation::new(3, |frame| {
+            #[allow(clippy::cast_precision_loss)]
+            let angle = frame as f64 * PI / 6.0;
+            let mut camera = Camera::new(4, 3, PI / 2.0);
+            camera.transform = Matrix::view_transform(
+                Matrix::rotation_y(angle) * Point::new(0.0, 0.0, -5.0),
+                Point::default(),
+                vector::Y,
+            );
+            (camera, test_world())
+        });
+
+        let mut reported = Vec::new();
+        let mut progress = |done, total| reported.push((done, total));
+        animation.render(&dir, Some(&mut progress)).unwrap();
+
+        assert_eq!(reported, vec![(1, 3), (2, 3), (3, 3)]);
+        for frame in 0..3 {
+            let ppm = fs::read_to_string(frame_path(&dir, frame)).unwrap();
+            assert!(ppm.starts_with("P3\n4 3\n255\n"));
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn render_io_error() {
+        let dir = std::env::temp_dir().join(format!(
+            "raytracer_animation_missing_{}",
+            std::process::id()
+        ));
+        let mut calls = 0;
+        {
+            let mut animation = Animation::new(3, |_| {
+                calls += 1;
+                (Camera::new(2, 2, PI / 2.0), test_world())
+            });
+            assert!(animation.render(&dir, None).is_err());
+        }
+        assert_eq!(calls, 1);
+    }
+}
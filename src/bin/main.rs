@@ -1,5 +1,4 @@
 use raytracer::pattern::StripePattern;
-use raytracer::transformations::Transformable;
 use raytracer::{
     point, vector, Camera, Color, Material, Matrix, Object, Pattern, Plane, Point, PointLight,
     Shape, Sphere, Vector, World,
@@ -29,8 +28,7 @@ fn main() {
     );
     sphere1
         .material
-        .pattern
-        .set_transform(Matrix::scaling(Vector::new(0.2, 0.2, 0.2)));
+        .set_pattern_transform(Matrix::scaling(Vector::new(0.2, 0.2, 0.2)));
 
     let sphere2 = Sphere::new(
         Matrix::translation(Vector::new(1.5, 0.5, -0.5))
@@ -0,0 +1,230 @@
+use crate::utils::EPSILON;
+use crate::{Matrix, Point, Ray};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    #[must_use]
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+
+    #[must_use]
+    pub fn empty() -> Self {
+        Self::new(
+            Point::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        )
+    }
+
+    #[must_use]
+    pub fn infinite() -> Self {
+        Self::new(
+            Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            Point::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        )
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    #[must_use]
+    pub fn is_finite(&self) -> bool {
+        [
+            self.min.x, self.min.y, self.min.z, self.max.x, self.max.y, self.max.z,
+        ]
+        .iter()
+        .all(|c| c.is_finite())
+    }
+
+    pub fn add_point(&mut self, point: Point) {
+        self.min = Point::new(
+            self.min.x.min(point.x),
+            self.min.y.min(point.y),
+            self.min.z.min(point.z),
+        );
+        self.max = Point::new(
+            self.max.x.max(point.x),
+            self.max.y.max(point.y),
+            self.max.z.max(point.z),
+        );
+    }
+
+    #[must_use]
+    pub fn merge(&self, other: &Self) -> Self {
+        let mut merged = *self;
+        merged.add_point(other.min);
+        merged.add_point(other.max);
+        merged
+    }
+
+    #[must_use]
+    pub fn contains_point(&self, point: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+
+    #[must_use]
+    pub fn contains_box(&self, other: &Self) -> bool {
+        other.is_empty() || (self.contains_point(other.min) && self.contains_point(other.max))
+    }
+
+    #[must_use]
+    pub fn intersects_ray(&self, ray: &Ray) -> bool {
+        self.ray_interval(ray)
+            .is_some_and(|(_, t_max)| t_max >= 0.0)
+    }
+
+    // The span of t, possibly negative, over which the ray's line is inside.
+    #[must_use]
+    pub fn ray_interval(&self, ray: &Ray) -> Option<(f64, f64)> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let (mut t_min, mut t_max) = (f64::NEG_INFINITY, f64::INFINITY);
+        for (origin, direction, min, max) in [
+            (ray.origin.x, ray.direction.x, self.min.x, self.max.x),
+            (ray.origin.y, ray.direction.y, self.min.y, self.max.y),
+            (ray.origin.z, ray.direction.z, self.min.z, self.max.z),
+        ] {
+            if direction.abs() < EPSILON {
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+
+            let (t0, t1) = ((min - origin) / direction, (max - origin) / direction);
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+        }
+        (t_min <= t_max).then_some((t_min, t_max))
+    }
+
+    #[must_use]
+    pub fn center(&self) -> Point {
+        Point::new(
+            f64::midpoint(self.min.x, self.max.x),
+            f64::midpoint(self.min.y, self.max.y),
+            f64::midpoint(self.min.z, self.max.z),
+        )
+    }
+
+    #[must_use]
+    pub fn transform(&self, matrix: Matrix) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        if !self.is_finite() {
+            return Self::infinite();
+        }
+
+        let mut transformed = Self::empty();
+        for x in [self.min.x, self.max.x] {
+            for y in [self.min.y, self.max.y] {
+                for z in [self.min.z, self.max.z] {
+                    transformed.add_point(matrix * Point::new(x, y, z));
+                }
+            }
+        }
+        transformed
+    }
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Vector;
+
+    use std::f64::consts::{PI, SQRT_2};
+
+    #[test]
+    fn merge_boxes() {
+        let a = BoundingBox::new(Point::new(-5.0, -2.0, 0.0), Point::new(7.0, 4.0, 4.0));
+        let b = BoundingBox::new(Point::new(8.0, -7.0, -2.0), Point::new(14.0, 2.0, 8.0));
+
+        assert_eq!(
+            a.merge(&b),
+            BoundingBox::new(Point::new(-5.0, -7.0, -2.0), Point::new(14.0, 4.0, 8.0))
+        );
+        assert_eq!(BoundingBox::empty().merge(&a), a);
+    }
+
+    #[test]
+    fn ray_box_intersection() {
+        let b = BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0));
+        let z = Vector::new(0.0, 0.0, 1.0);
+
+        assert!(b.intersects_ray(&Ray::new(Point::new(0.5, 0.5, -5.0), z)));
+        assert!(b.intersects_ray(&Ray::new(Point::default(), z)));
+        assert_eq!(
+            b.ray_interval(&Ray::new(Point::default(), z)),
+            Some((-1.0, 1.0))
+        );
+        assert!(!b.intersects_ray(&Ray::new(Point::new(2.0, 0.0, -5.0), z)));
+        assert!(!b.intersects_ray(&Ray::new(Point::new(0.0, 0.0, 5.0), z)));
+        assert!(!b.intersects_ray(&Ray::new(
+            Point::new(-5.0, 0.0, 0.0),
+            Vector::new(1.0, 1.0, 0.0).normalize()
+        )));
+        assert!(!BoundingBox::empty().intersects_ray(&Ray::new(Point::default(), z)));
+
+        let flat = BoundingBox::new(Point::new(-1.0, 0.0, -1.0), Point::new(1.0, 0.0, 1.0));
+        assert!(flat.intersects_ray(&Ray::new(
+            Point::new(0.0, 5.0, 0.0),
+            Vector::new(0.0, -1.0, 0.0)
+        )));
+        assert!(!flat.intersects_ray(&Ray::new(Point::new(0.0, 5.0, 0.0), z)));
+    }
+
+    #[test]
+    fn box_containment() {
+        let b = BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0));
+
+        assert!(b.contains_point(Point::new(0.5, -1.0, 0.0)));
+        assert!(!b.contains_point(Point::new(0.5, -1.5, 0.0)));
+        assert!(b.contains_box(&BoundingBox::new(
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 0.5, 0.5)
+        )));
+        assert!(!b.contains_box(&BoundingBox::new(
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(2.0, 0.5, 0.5)
+        )));
+        assert!(b.contains_box(&BoundingBox::empty()));
+        assert!(!BoundingBox::empty().contains_point(Point::default()));
+    }
+
+    #[test]
+    fn transform_box() {
+        let b = BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0));
+        let matrix = Matrix::rotation_x(PI / 4.0) * Matrix::rotation_y(PI / 4.0);
+
+        assert_eq!(
+            b.transform(matrix),
+            BoundingBox::new(
+                Point::new(-SQRT_2, -1.70711, -1.70711),
+                Point::new(SQRT_2, 1.70711, 1.70711)
+            )
+        );
+        assert_eq!(
+            b.transform(Matrix::translation(Vector::new(1.0, 2.0, 3.0))),
+            BoundingBox::new(Point::new(0.0, 1.0, 2.0), Point::new(2.0, 3.0, 4.0))
+        );
+        assert!(!BoundingBox::infinite().transform(matrix).is_finite());
+    }
+}
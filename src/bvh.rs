@@ -0,0 +1,192 @@
+use crate::{BoundingBox, Object, Ray, Shape};
+
+const LEAF_SIZE: usize = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Node {
+    bounds: BoundingBox,
+    indices: Vec<usize>,
+    children: Option<Box<(Node, Node)>>,
+}
+
+// Hierarchy over indices into `World::objects`. Objects with unbounded
+// extents (planes) can't be boxed, so they are always tested.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bvh {
+    unbounded: Vec<usize>,
+    root: Option<Node>,
+}
+
+impl Bvh {
+    #[must_use]
+    pub fn build(objects: &[Object]) -> Self {
+        let (bounded, unbounded): (Vec<_>, Vec<_>) = objects
+            .iter()
+            .map(Shape::parent_space_bounds)
+            .enumerate()
+            .filter(|(_, bounds)| !bounds.is_empty())
+            .partition(|(_, bounds)| bounds.is_finite());
+
+        Self {
+            unbounded: unbounded.into_iter().map(|(index, _)| index).collect(),
+            root: if bounded.is_empty() {
+                None
+            } else {
+                Some(Node::build(bounded))
+            },
+        }
+    }
+
+    // Calls `visit` with every object index whose bounds the ray may hit,
+    // stopping early once `visit` returns true.
+    pub fn traverse(&self, ray: &Ray, visit: &mut impl FnMut(usize) -> bool) -> bool {
+        self.unbounded.iter().any(|&index| visit(index))
+            || self
+                .root
+                .as_ref()
+                .is_some_and(|root| root.traverse(ray, visit))
+    }
+}
+
+impl Node {
+    fn build(mut items: Vec<(usize, BoundingBox)>) -> Self {
+        let bounds = items
+            .iter()
+            .fold(BoundingBox::empty(), |acc, (_, b)| acc.merge(b));
+
+        let mut centroids = BoundingBox::empty();
+        for (_, b) in &items {
+            centroids.add_point(b.center());
+        }
+        let extent = centroids.max - centroids.min;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+
+        if items.len() <= LEAF_SIZE || [extent.x, extent.y, extent.z][axis] <= 0.0 {
+            return Self {
+                bounds,
+                indices: items.into_iter().map(|(index, _)| index).collect(),
+                children: None,
+            };
+        }
+
+        let key = |b: &BoundingBox| {
+            let center = b.center();
+            [center.x, center.y, center.z][axis]
+        };
+        items.sort_by(|(_, a), (_, b)| key(a).total_cmp(&key(b)));
+        let right = items.split_off(items.len() / 2);
+
+        Self {
+            bounds,
+            indices: Vec::new(),
+            children: Some(Box::new((Self::build(items), Self::build(right)))),
+        }
+    }
+
+    fn traverse(&self, ray: &Ray, visit: &mut impl FnMut(usize) -> bool) -> bool {
+        if !self.bounds.intersects_ray(ray) {
+            return false;
+        }
+
+        match &self.children {
+            Some(children) => children.0.traverse(ray, visit) || children.1.traverse(ray, visit),
+            None => self.indices.iter().any(|&index| visit(index)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{vector, Material, Matrix, Mesh, Plane, Point, Sphere, Vector};
+
+    fn sphere_at(x: f64) -> Object {
+        Object::Sphere(Sphere::new(
+            Matrix::translation(Vector::new(x, 0.0, 0.0)),
+            Material::default(),
+        ))
+    }
+
+    fn visited(bvh: &Bvh, ray: &Ray) -> Vec<usize> {
+        let mut indices = Vec::new();
+        bvh.traverse(ray, &mut |index| {
+            indices.push(index);
+            false
+        });
+        indices.sort_unstable();
+        indices
+    }
+
+    #[test]
+    fn bounds_cover_every_object() {
+        let objects: Vec<_> = (0..10).map(|i| sphere_at(f64::from(i) * 3.0)).collect();
+        let bvh = Bvh::build(&objects);
+        let root = bvh.root.as_ref().unwrap();
+
+        assert_eq!(
+            root.bounds,
+            BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(28.0, 1.0, 1.0))
+        );
+        assert!(root.children.is_some());
+        assert!(bvh.unbounded.is_empty());
+    }
+
+    #[test]
+    fn traversal_skips_missed_objects() {
+        let objects: Vec<_> = (0..10).map(|i| sphere_at(f64::from(i) * 3.0)).collect();
+        let bvh = Bvh::build(&objects);
+
+        let ray = Ray::new(Point::new(27.0, 0.0, -5.0), vector::Z);
+        let indices = visited(&bvh, &ray);
+        assert!(indices.contains(&9));
+        assert!(indices.len() <= LEAF_SIZE);
+
+        let ray = Ray::new(Point::new(0.0, 5.0, -5.0), vector::Z);
+        assert!(visited(&bvh, &ray).is_empty());
+    }
+
+    #[test]
+    fn traversal_stops_once_visit_returns_true() {
+        let objects: Vec<_> = (0..10).map(|_| sphere_at(0.0)).collect();
+        let bvh = Bvh::build(&objects);
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
+
+        let mut count = 0;
+        assert!(bvh.traverse(&ray, &mut |_| {
+            count += 1;
+            true
+        }));
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn unbounded_objects_are_always_visited() {
+        let objects = vec![Object::Plane(Plane::default()), sphere_at(0.0)];
+        let bvh = Bvh::build(&objects);
+        assert_eq!(bvh.unbounded, [0]);
+
+        let ray = Ray::new(Point::new(10.0, 5.0, -5.0), vector::Z);
+        assert_eq!(visited(&bvh, &ray), [0]);
+    }
+
+    #[test]
+    fn empty_objects_are_never_visited() {
+        let bvh = Bvh::build(&[]);
+        assert!(bvh.root.is_none());
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
+        assert!(visited(&bvh, &ray).is_empty());
+
+        let objects = vec![
+            Object::Mesh(Mesh::new(Vec::new(), Vec::new())),
+            sphere_at(0.0),
+        ];
+        let bvh = Bvh::build(&objects);
+        assert_eq!(visited(&bvh, &ray), [1]);
+    }
+}
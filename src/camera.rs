@@ -1,4 +1,86 @@
-use crate::{Canvas, Matrix, Point, Ray, World};
+use crate::rng::Rng;
+use crate::scene_hash::SceneHash;
+use crate::{vector, Canvas, Color, Intersection, Matrix, Point, Ray, Shape, Vector, World};
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+const EDGE_BISECTIONS: usize = 8;
+const EDGE_SUPERSAMPLES: usize = 4;
+const PROGRESSIVE_BLOCKS: [usize; 4] = [8, 4, 2, 1];
+const SAMPLER_SEED: u64 = 0x5a3d;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sampler {
+    Grid,
+    Jitter,
+    MultiJitter,
+}
+
+impl Sampler {
+    // Sub-pixel offsets in [0, 1)^2. The samples are laid out on a
+    // `columns` x `rows` grid with `columns` the largest divisor of `count`
+    // not above its square root. A count of zero still takes one sample.
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn offsets(self, count: usize, rng: &mut Rng) -> Vec<(f64, f64)> {
+        let count = count.max(1);
+        let columns = (1..=count)
+            .take_while(|c| c * c <= count)
+            .filter(|c| count.is_multiple_of(*c))
+            .last()
+            .unwrap_or(1);
+        let rows = count / columns;
+        let (width, height) = (1.0 / columns as f64, 1.0 / rows as f64);
+
+        let mut offsets = Vec::with_capacity(count);
+        for row in 0..rows {
+            for column in 0..columns {
+                let (x, y) = (column as f64, row as f64);
+                offsets.push(match self {
+                    Sampler::Grid => ((x + 0.5) * width, (y + 0.5) * height),
+                    Sampler::Jitter => {
+                        ((x + rng.next_f64()) * width, (y + rng.next_f64()) * height)
+                    }
+                    Sampler::MultiJitter => (
+                        (x + (y + rng.next_f64()) * height) * width,
+                        (y + (x + rng.next_f64()) * width) * height,
+                    ),
+                });
+            }
+        }
+
+        // Swapping x between rows or y between columns keeps every cell,
+        // sub-column and sub-row covered exactly once. Every column shares one
+        // shuffle (and every row another), as in correlated multi-jittering.
+        if self == Sampler::MultiJitter {
+            for row in (1..rows).rev() {
+                let other = random_below(rng, row + 1);
+                for column in 0..columns {
+                    let (a, b) = (row * columns + column, other * columns + column);
+                    let x = offsets[a].0;
+                    offsets[a].0 = offsets[b].0;
+                    offsets[b].0 = x;
+                }
+            }
+            for column in (1..columns).rev() {
+                let other = random_below(rng, column + 1);
+                for row in 0..rows {
+                    let (a, b) = (row * columns + column, row * columns + other);
+                    let y = offsets[a].1;
+                    offsets[a].1 = offsets[b].1;
+                    offsets[b].1 = y;
+                }
+            }
+        }
+        offsets
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn random_below(rng: &mut Rng, bound: usize) -> usize {
+    (rng.next_u64() % bound as u64) as usize
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Camera {
@@ -36,11 +118,49 @@ impl Camera {
         }
     }
 
+    #[must_use]
+    pub fn config_hash(&self) -> u64 {
+        self.quantized_hash()
+    }
+
+    pub fn look_at(&mut self, from: Point, to: Point, up: Vector) {
+        self.transform = Matrix::view_transform(from, to, up);
+    }
+
+    pub fn front_view(&mut self, target: Point, distance: f64) {
+        self.look_at(target - vector::Z * distance, target, vector::Y);
+    }
+
+    pub fn top_view(&mut self, target: Point, distance: f64) {
+        self.look_at(target + vector::Y * distance, target, vector::Z);
+    }
+
+    pub fn iso_view(&mut self, target: Point, distance: f64) {
+        let offset = Vector::new(1.0, 1.0, -1.0).normalize() * distance;
+        self.look_at(target + offset, target, vector::Y);
+    }
+
+    pub fn frame_scene(&mut self, world: &World, margin: f64) {
+        let bounds = world.bounds();
+        if bounds.is_empty() {
+            return;
+        }
+
+        let center = bounds.center();
+        let radius = (bounds.max - center).magnitude() * (1.0 + margin);
+        let half_angle = self.half_width.min(self.half_height).atan();
+        self.front_view(center, radius / half_angle.sin());
+    }
+
     #[allow(clippy::cast_precision_loss)]
     #[must_use]
     pub fn ray_for_pixel(&self, x: usize, y: usize) -> Ray {
-        let xoffset = (x as f64 + 0.5) * self.pixel_size;
-        let yoffset = (y as f64 + 0.5) * self.pixel_size;
+        self.ray_for_offset(x as f64 + 0.5, y as f64 + 0.5)
+    }
+
+    fn ray_for_offset(&self, px: f64, py: f64) -> Ray {
+        let xoffset = px * self.pixel_size;
+        let yoffset = py * self.pixel_size;
 
         let world_x = self.half_width - xoffset;
         let world_y = self.half_height - yoffset;
@@ -50,30 +170,279 @@ impl Camera {
         let origin = transform_inv * Point::default();
         let direction = (pixel - origin).normalize();
 
-        Ray::new(origin, direction)
+        Ray::new(origin, direction).with_width(self.pixel_size / 2.0)
     }
 
     #[must_use]
     pub fn render(&self, world: &World) -> Canvas {
         let mut image = Canvas::new(self.h_size, self.v_size);
+        let mut intersections = Vec::new();
         for y in 0..self.v_size {
             for x in 0..self.h_size {
                 let ray = self.ray_for_pixel(x, y);
-                let color = world.color_at(&ray);
+                let color =
+                    world.color_at_into(&ray, world.max_recursion_depth, &mut intersections);
                 image.write_pixel(x, y, color);
             }
         }
 
         image
     }
+
+    // Returns the image along with the number of pixels that came out NaN or
+    // infinite; use `Canvas::find_invalid` to locate them.
+    #[must_use]
+    pub fn render_checked(&self, world: &World) -> (Canvas, usize) {
+        let image = self.render(world);
+        let invalid = image.find_invalid().len();
+        (image, invalid)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn render_edge_aa(&self, world: &World) -> Canvas {
+        let hit_id = |px: f64, py: f64| {
+            let ray = self.ray_for_offset(px, py);
+            Intersection::hit(&world.intersect(&ray)).map(|hit| hit.object.id())
+        };
+
+        let mut image = Canvas::new(self.h_size, self.v_size);
+        for y in 0..self.v_size {
+            for x in 0..self.h_size {
+                let (x0, y0) = (x as f64, y as f64);
+                let corners = [
+                    (x0, y0),
+                    (x0 + 1.0, y0),
+                    (x0 + 1.0, y0 + 1.0),
+                    (x0, y0 + 1.0),
+                ];
+                let ids = corners.map(|(px, py)| hit_id(px, py));
+
+                let mut distinct = ids.to_vec();
+                distinct.sort_unstable();
+                distinct.dedup();
+
+                let color = match distinct.len() {
+                    1 => world.color_at(&self.ray_for_pixel(x, y), world.max_recursion_depth),
+                    2 => {
+                        let mut coverage = 0.0;
+                        for i in 0..4 {
+                            let j = (i + 1) % 4;
+                            coverage += if ids[i] == ids[j] {
+                                if ids[i] == ids[0] {
+                                    1.0
+                                } else {
+                                    0.0
+                                }
+                            } else {
+                                let (mut lo, mut hi) = (0.0, 1.0);
+                                for _ in 0..EDGE_BISECTIONS {
+                                    let mid = f64::midpoint(lo, hi);
+                                    let (px, py) = (
+                                        corners[i].0 + (corners[j].0 - corners[i].0) * mid,
+                                        corners[i].1 + (corners[j].1 - corners[i].1) * mid,
+                                    );
+                                    if hit_id(px, py) == ids[i] {
+                                        lo = mid;
+                                    } else {
+                                        hi = mid;
+                                    }
+                                }
+                                let t = f64::midpoint(lo, hi);
+                                if ids[i] == ids[0] {
+                                    t
+                                } else {
+                                    1.0 - t
+                                }
+                            };
+                        }
+                        coverage /= 4.0;
+
+                        let other = (1..4).find(|&i| ids[i] != ids[0]).unwrap();
+                        let color_at_corner = |i: usize| {
+                            let (px, py) = corners[i];
+                            world.color_at(&self.ray_for_offset(px, py), world.max_recursion_depth)
+                        };
+                        color_at_corner(0) * coverage + color_at_corner(other) * (1.0 - coverage)
+                    }
+                    _ => {
+                        let mut total = Color::black();
+                        for sy in 0..EDGE_SUPERSAMPLES {
+                            for sx in 0..EDGE_SUPERSAMPLES {
+                                let ray = self.ray_for_offset(
+                                    x0 + (sx as f64 + 0.5) / EDGE_SUPERSAMPLES as f64,
+                                    y0 + (sy as f64 + 0.5) / EDGE_SUPERSAMPLES as f64,
+                                );
+                                total = total + world.color_at(&ray, world.max_recursion_depth);
+                            }
+                        }
+                        total * (1.0 / (EDGE_SUPERSAMPLES * EDGE_SUPERSAMPLES) as f64)
+                    }
+                };
+                image.write_pixel(x, y, color);
+            }
+        }
+
+        image
+    }
+
+    // Gray levels show how much of the light reaches each visible point, or
+    // None if the world has no light at `light_index`.
+    #[must_use]
+    pub fn render_light_coverage(&self, world: &World, light_index: usize) -> Option<Canvas> {
+        let light = world.lights().get(light_index)?;
+        let mut image = Canvas::new(self.h_size, self.v_size);
+        for y in 0..self.v_size {
+            for x in 0..self.h_size {
+                let ray = self.ray_for_pixel(x, y);
+                let intersections = world.intersect(&ray);
+                if let Some(hit) = Intersection::hit(&intersections) {
+                    let comps = hit.prepare_computations(&ray, &intersections);
+                    let coverage = light.intensity_at(comps.over_point, world);
+                    image.write_pixel(x, y, Color::white() * coverage);
+                }
+            }
+        }
+
+        Some(image)
+    }
+
+    pub fn render_progressive(&self, world: &World, mut on_pass: impl FnMut(&Canvas, usize)) {
+        let mut image = Canvas::new(self.h_size, self.v_size);
+        for block in PROGRESSIVE_BLOCKS {
+            for y in (0..self.v_size).step_by(block) {
+                for x in (0..self.h_size).step_by(block) {
+                    let sampled = block < PROGRESSIVE_BLOCKS[0]
+                        && x.is_multiple_of(block * 2)
+                        && y.is_multiple_of(block * 2);
+                    let color = if sampled {
+                        *image.pixel_at(x, y)
+                    } else {
+                        world.color_at(&self.ray_for_pixel(x, y), world.max_recursion_depth)
+                    };
+
+                    for py in y..(y + block).min(self.v_size) {
+                        for px in x..(x + block).min(self.h_size) {
+                            image.write_pixel(px, py, color);
+                        }
+                    }
+                }
+            }
+            on_pass(&image, block);
+        }
+    }
+
+    #[must_use]
+    pub fn render_tiled(&self, world: &World, tile_size: usize, threads: usize) -> Canvas {
+        let tile_size = tile_size.max(1);
+        let tiles: Vec<(usize, usize)> = (0..self.v_size)
+            .step_by(tile_size)
+            .flat_map(|y| (0..self.h_size).step_by(tile_size).map(move |x| (x, y)))
+            .collect();
+        let next_tile = AtomicUsize::new(0);
+
+        let rendered: Vec<(usize, usize, Canvas)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads.max(1))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        while let Some(&(x, y)) =
+                            tiles.get(next_tile.fetch_add(1, Ordering::Relaxed))
+                        {
+                            let width = tile_size.min(self.h_size - x);
+                            let height = tile_size.min(self.v_size - y);
+                            done.push((x, y, self.render_region(world, x, y, width, height)));
+                        }
+                        done
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect()
+        });
+
+        let mut image = Canvas::new(self.h_size, self.v_size);
+        for (x0, y0, tile) in rendered {
+            for y in 0..tile.height() {
+                for x in 0..tile.width() {
+                    image.write_pixel(x0 + x, y0 + y, *tile.pixel_at(x, y));
+                }
+            }
+        }
+
+        image
+    }
+
+    #[must_use]
+    pub fn render_crop(
+        &self,
+        world: &World,
+        x0: usize,
+        y0: usize,
+        width: usize,
+        height: usize,
+    ) -> Canvas {
+        let (x0, y0) = (x0.min(self.h_size), y0.min(self.v_size));
+        let width = width.min(self.h_size - x0);
+        let height = height.min(self.v_size - y0);
+        self.render_region(world, x0, y0, width, height)
+    }
+
+    fn render_region(
+        &self,
+        world: &World,
+        x0: usize,
+        y0: usize,
+        width: usize,
+        height: usize,
+    ) -> Canvas {
+        let mut region = Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let ray = self.ray_for_pixel(x0 + x, y0 + y);
+                region.write_pixel(x, y, world.color_at(&ray, world.max_recursion_depth));
+            }
+        }
+
+        region
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn render_sampled(&self, world: &World, sampler: Sampler, count: usize) -> Canvas {
+        let mut rng = Rng::new(SAMPLER_SEED);
+        let mut image = Canvas::new(self.h_size, self.v_size);
+        let mut intersections = Vec::new();
+        for y in 0..self.v_size {
+            for x in 0..self.h_size {
+                let offsets = sampler.offsets(count, &mut rng);
+                let total = offsets.iter().fold(Color::black(), |acc, (u, v)| {
+                    let ray = self.ray_for_offset(x as f64 + u, y as f64 + v);
+                    acc + world.color_at_into(&ray, world.max_recursion_depth, &mut intersections)
+                });
+                image.write_pixel(x, y, total * (1.0 / offsets.len() as f64));
+            }
+        }
+
+        image
+    }
+
+    #[must_use]
+    pub fn render_to_rgba8(&self, world: &World) -> (Vec<u8>, usize, usize) {
+        (self.render(world).to_rgba8(), self.h_size, self.v_size)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::light::test_light::{area_light, occluder};
     use crate::utils::equal;
     use crate::world::test_world::test_world;
-    use crate::{vector, Color, Vector};
+    use crate::{Material, Object, Plane, PointLight, Sphere};
     use std::f64::consts::PI;
 
     #[test]
@@ -121,6 +490,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn look_at() {
+        let from = Point::new(1.0, 3.0, 2.0);
+        let to = Point::new(4.0, -2.0, 8.0);
+        let up = vector::Y + vector::X;
+        let mut c = Camera::new(11, 11, PI / 2.0);
+        c.look_at(from, to, up);
+
+        assert_eq!(c.transform, Matrix::view_transform(from, to, up));
+    }
+
+    #[test]
+    fn preset_views() {
+        let target = Point::new(1.0, 2.0, 3.0);
+        let mut c = Camera::new(11, 11, PI / 2.0);
+
+        c.top_view(target, 10.0);
+        let r = c.ray_for_pixel(5, 5);
+        assert_eq!(r.origin, Point::new(1.0, 12.0, 3.0));
+        assert_eq!(r.direction, -vector::Y);
+
+        c.front_view(target, 10.0);
+        let r = c.ray_for_pixel(5, 5);
+        assert_eq!(r.origin, Point::new(1.0, 2.0, -7.0));
+        assert_eq!(r.direction, vector::Z);
+
+        c.iso_view(target, 3_f64.sqrt());
+        let r = c.ray_for_pixel(5, 5);
+        assert_eq!(r.origin, Point::new(2.0, 3.0, 2.0));
+        assert_eq!(r.direction, Vector::new(-1.0, -1.0, 1.0).normalize());
+    }
+
     #[test]
     fn render_world() {
         let world = test_world();
@@ -130,4 +531,279 @@ mod tests {
         let image = c.render(&world);
         assert_eq!(image.pixel_at(5, 5), &Color::new(0.38066, 0.47583, 0.2855));
     }
+
+    #[test]
+    fn render_rgba8() {
+        let world = test_world();
+        let mut c = Camera::new(2, 2, PI / 2.0);
+        c.transform =
+            Matrix::view_transform(Point::new(0.0, 0.0, -5.0), Point::default(), vector::Y);
+        let (bytes, width, height) = c.render_to_rgba8(&world);
+
+        assert_eq!((width, height), (2, 2));
+        assert_eq!(bytes.len(), 2 * 2 * 4);
+        assert_eq!(bytes, c.render(&world).to_rgba8());
+        assert_eq!(bytes[3], 255);
+    }
+
+    #[test]
+    fn render_tiled() {
+        let world = test_world();
+        let mut c = Camera::new(21, 13, PI / 2.0);
+        c.transform =
+            Matrix::view_transform(Point::new(0.0, 0.0, -5.0), Point::default(), vector::Y);
+        let serial = c.render(&world);
+
+        for (tile_size, threads) in [(8, 4), (64, 2), (1, 3)] {
+            let tiled = c.render_tiled(&world, tile_size, threads);
+            for y in 0..c.v_size {
+                for x in 0..c.h_size {
+                    assert_eq!(tiled.pixel_at(x, y), serial.pixel_at(x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn render_checked_counts_invalid_pixels() {
+        let world = test_world();
+        let mut c = Camera::new(11, 11, PI / 2.0);
+        c.transform =
+            Matrix::view_transform(Point::new(0.0, 0.0, -5.0), Point::default(), vector::Y);
+
+        let (image, invalid) = c.render_checked(&world);
+        assert_eq!(image, c.render(&world));
+        assert_eq!(invalid, 0);
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    #[test]
+    fn multi_jitter_offsets() {
+        for (count, columns) in [(16, 4), (12, 3), (7, 1)] {
+            let offsets = Sampler::MultiJitter.offsets(count, &mut Rng::new(9));
+            assert_eq!(offsets.len(), count);
+            assert!(offsets
+                .iter()
+                .all(|(x, y)| (0.0..1.0).contains(x) && (0.0..1.0).contains(y)));
+
+            let strata = |coordinate: fn(&(f64, f64)) -> f64| {
+                let mut strata: Vec<usize> = offsets
+                    .iter()
+                    .map(|o| (coordinate(o) * count as f64) as usize)
+                    .collect();
+                strata.sort_unstable();
+                strata
+            };
+            let all: Vec<usize> = (0..count).collect();
+            assert_eq!(strata(|o| o.0), all);
+            assert_eq!(strata(|o| o.1), all);
+
+            let mut cells: Vec<(usize, usize)> = offsets
+                .iter()
+                .map(|(x, y)| {
+                    (
+                        (x * columns as f64) as usize,
+                        (y * (count / columns) as f64) as usize,
+                    )
+                })
+                .collect();
+            cells.sort_unstable();
+            cells.dedup();
+            assert_eq!(cells.len(), count);
+
+            // Correlated: each row's x falls in the same sub-column of every
+            // cell, and each column's y in the same sub-row.
+            let rows = count / columns;
+            let sub = |value: f64, cells: usize, parts: usize| {
+                ((value * cells as f64).fract() * parts as f64) as usize
+            };
+            for (index, (x, y)) in offsets.iter().enumerate() {
+                let (row, column) = (index / columns, index % columns);
+                assert_eq!(
+                    sub(*x, columns, rows),
+                    sub(offsets[row * columns].0, columns, rows)
+                );
+                assert_eq!(
+                    sub(*y, rows, columns),
+                    sub(offsets[column].1, rows, columns)
+                );
+            }
+
+            assert_eq!(
+                offsets,
+                Sampler::MultiJitter.offsets(count, &mut Rng::new(9))
+            );
+            assert_ne!(
+                offsets,
+                Sampler::MultiJitter.offsets(count, &mut Rng::new(10))
+            );
+        }
+
+        assert_eq!(
+            Sampler::Grid.offsets(4, &mut Rng::new(0)),
+            vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+        );
+    }
+
+    #[test]
+    fn render_sampled_flat_region() {
+        let world = test_world();
+        let mut c = Camera::new(5, 5, PI / 60.0);
+        c.transform =
+            Matrix::view_transform(Point::new(0.0, 0.0, -5.0), Point::default(), vector::Y);
+
+        let image = c.render_sampled(&world, Sampler::MultiJitter, 9);
+        assert!(image
+            .pixel_at(2, 2)
+            .approx_eq(c.render(&world).pixel_at(2, 2), 0.05));
+    }
+
+    #[test]
+    fn render_sampled_zero_count() {
+        assert_eq!(
+            Sampler::Jitter.offsets(0, &mut Rng::new(0)),
+            Sampler::Jitter.offsets(1, &mut Rng::new(0))
+        );
+
+        let world = test_world();
+        let c = Camera::new(3, 3, PI / 2.0);
+        let image = c.render_sampled(&world, Sampler::Grid, 0);
+        assert_eq!(image, c.render_sampled(&world, Sampler::Grid, 1));
+        assert!(!image.pixel_at(1, 1).r.is_nan());
+    }
+
+    #[test]
+    fn render_crop() {
+        let world = test_world();
+        let mut c = Camera::new(21, 13, PI / 2.0);
+        c.transform =
+            Matrix::view_transform(Point::new(0.0, 0.0, -5.0), Point::default(), vector::Y);
+        let full = c.render(&world);
+
+        let crop = c.render_crop(&world, 7, 4, 6, 5);
+        assert_eq!((crop.width(), crop.height()), (6, 5));
+        for y in 0..5 {
+            for x in 0..6 {
+                assert_eq!(crop.pixel_at(x, y), full.pixel_at(7 + x, 4 + y));
+            }
+        }
+
+        let clamped = c.render_crop(&world, 18, 10, 10, 10);
+        assert_eq!((clamped.width(), clamped.height()), (3, 3));
+        assert_eq!(clamped.pixel_at(2, 2), full.pixel_at(20, 12));
+        assert_eq!(c.render_crop(&world, 30, 0, 5, 5).width(), 0);
+    }
+
+    #[test]
+    fn render_light_coverage() {
+        let floor = Object::Plane(Plane::default());
+        let occluder = Object::Sphere(Sphere::new(
+            Matrix::translation(Vector::new(2.0, 5.0, 0.0)),
+            Material::default(),
+        ));
+        let light = PointLight::new(Point::new(0.0, 10.0, 0.0), Color::white());
+        let world = World::new(vec![floor, occluder], light);
+
+        let mut c = Camera::new(1, 1, PI / 3.0);
+        c.look_at(Point::new(-6.0, 1.0, 0.0), Point::default(), vector::Y);
+        assert_eq!(
+            c.render_light_coverage(&world, 0).unwrap().pixel_at(0, 0),
+            &Color::white()
+        );
+
+        c.look_at(
+            Point::new(-6.0, 1.0, 0.0),
+            Point::new(4.0, 0.0, 0.0),
+            vector::Y,
+        );
+        assert_eq!(
+            c.render_light_coverage(&world, 0).unwrap().pixel_at(0, 0),
+            &Color::black()
+        );
+        assert!(c.render_light_coverage(&world, 1).is_none());
+    }
+
+    #[test]
+    fn render_area_light_coverage() {
+        let floor = Object::Plane(Plane::default());
+        let world = World::new(
+            vec![floor, occluder(5.0, Material::default())],
+            area_light(4),
+        );
+
+        let mut c = Camera::new(1, 1, PI / 3.0);
+        c.look_at(Point::new(-6.0, 1.0, 0.0), Point::default(), vector::Y);
+        let coverage = c.render_light_coverage(&world, 0).unwrap().pixel_at(0, 0).r;
+        assert!((coverage - 0.5).abs() < 0.1, "coverage {}", coverage);
+    }
+
+    #[test]
+    fn frame_scene() {
+        let s1 = Object::Sphere(Sphere::new(
+            Matrix::translation(Vector::new(-3.0, 0.0, 0.0)),
+            Material::default(),
+        ));
+        let s2 = Object::Sphere(Sphere::new(
+            Matrix::translation(Vector::new(3.0, 0.0, 0.0)),
+            Material::default(),
+        ));
+        let world = World::new(vec![s1, s2], PointLight::default());
+
+        let mut c = Camera::new(40, 20, PI / 3.0);
+        c.frame_scene(&world, 0.1);
+
+        for corner in [Point::new(-4.0, 1.0, 0.0), Point::new(4.0, -1.0, 0.0)] {
+            let p = c.transform * corner;
+            assert!(p.z < 0.0);
+            assert!((p.x / -p.z).abs() < c.half_width);
+            assert!((p.y / -p.z).abs() < c.half_height);
+        }
+    }
+
+    #[test]
+    fn render_edge_aa() {
+        let sphere = Object::Sphere(Sphere::new(
+            Matrix::default(),
+            Material {
+                ambient: 1.0,
+                diffuse: 0.0,
+                specular: 0.0,
+                ..Default::default()
+            },
+        ));
+        let world = World::new(vec![sphere], PointLight::default());
+        let mut c = Camera::new(11, 11, PI / 2.0);
+        c.front_view(Point::default(), 5.0);
+
+        let plain = c.render(&world);
+        let smooth = c.render_edge_aa(&world);
+
+        assert_eq!(smooth.pixel_at(5, 5), plain.pixel_at(5, 5));
+        assert_eq!(smooth.pixel_at(0, 0), &Color::black());
+        assert!((0..11).any(|x| {
+            let r = smooth.pixel_at(x, 5).r;
+            r > 0.05 && r < 0.95
+        }));
+    }
+
+    #[test]
+    fn render_progressive() {
+        let world = test_world();
+        let mut c = Camera::new(21, 13, PI / 2.0);
+        c.front_view(Point::default(), 5.0);
+
+        let mut blocks = Vec::new();
+        let mut last = None;
+        c.render_progressive(&world, |image, block| {
+            blocks.push(block);
+            last = Some(image.clone());
+        });
+
+        assert_eq!(blocks, [8, 4, 2, 1]);
+        assert_eq!(last.unwrap(), c.render(&world));
+    }
 }
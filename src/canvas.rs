@@ -1,11 +1,38 @@
+use crate::error::RaytracerError;
 use crate::Color;
 
 use grid::Grid;
 
 use std::fs::File;
-use std::io::Write;
+use std::io::{self, BufWriter, Write};
 use std::path::Path;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlendMode {
+    Add,
+    Multiply,
+    Over(f64),
+    Average,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClampMode {
+    PerChannel,
+    PreserveHue,
+}
+
+impl ClampMode {
+    #[must_use]
+    pub fn apply(self, color: Color) -> Color {
+        let max = color.r.max(color.g).max(color.b);
+        match self {
+            ClampMode::PreserveHue if max > 1.0 => color * (1.0 / max),
+            _ => color,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Canvas {
     width: usize,
     height: usize,
@@ -22,6 +49,78 @@ impl Canvas {
         }
     }
 
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // Reads a plain (P3) or raw (P6) PPM. With `linearize` the samples are
+    // treated as sRGB-encoded and decoded to linear before being stored.
+    pub fn from_ppm(data: &[u8], linearize: bool) -> Result<Canvas, RaytracerError> {
+        let mut reader = PpmReader { data, pos: 0 };
+        let magic = reader.token()?;
+        let width = reader.number()?;
+        let height = reader.number()?;
+        let max = reader.number()?;
+        if !(1..=65535).contains(&max) {
+            return Err(RaytracerError::InvalidPpm);
+        }
+
+        let binary = match magic {
+            b"P3" => false,
+            b"P6" => {
+                reader.pos += 1;
+                true
+            }
+            _ => return Err(RaytracerError::InvalidPpm),
+        };
+
+        // The header is untrusted; every sample takes at least one byte (two
+        // for wide binary samples), so reject sizes the data can't fill.
+        let sample_len = if binary && max > 255 { 2 } else { 1 };
+        let remaining = data.len().saturating_sub(reader.pos);
+        width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(3 * sample_len))
+            .filter(|&len| len <= remaining)
+            .ok_or(RaytracerError::InvalidPpm)?;
+
+        #[allow(clippy::cast_precision_loss)]
+        let scale = 1.0 / max as f64;
+        let mut canvas = Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let mut channels = [0.0; 3];
+                for channel in &mut channels {
+                    let sample = if binary {
+                        reader.sample(max > 255)?
+                    } else {
+                        reader.number()?
+                    };
+                    if sample > max {
+                        return Err(RaytracerError::InvalidPpm);
+                    }
+                    #[allow(clippy::cast_precision_loss)]
+                    let value = sample as f64 * scale;
+                    *channel = value;
+                }
+                let color = Color::new(channels[0], channels[1], channels[2]);
+                let color = if linearize {
+                    color.srgb_to_linear()
+                } else {
+                    color
+                };
+                canvas.write_pixel(x, y, color);
+            }
+        }
+        Ok(canvas)
+    }
+
     pub fn write_pixel(&mut self, x: usize, y: usize, color: Color) {
         let pixel = self.canvas.get_mut(y, x).unwrap();
         *pixel = color;
@@ -33,8 +132,128 @@ impl Canvas {
     }
 
     #[must_use]
-    #[allow(clippy::cast_possible_truncation)]
+    pub fn find_invalid(&self) -> Vec<(usize, usize)> {
+        let mut invalid = Vec::new();
+        for y in 0..self.height {
+            for (x, color) in self.canvas.iter_row(y).enumerate() {
+                if !color.is_finite() {
+                    invalid.push((x, y));
+                }
+            }
+        }
+        invalid
+    }
+
+    pub fn blend(&self, other: &Canvas, mode: BlendMode) -> Result<Canvas, RaytracerError> {
+        if (self.width, self.height) != (other.width, other.height) {
+            return Err(RaytracerError::SizeMismatch {
+                expected: (self.width, self.height),
+                found: (other.width, other.height),
+            });
+        }
+
+        let mut blended = Canvas::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (a, b) = (*self.pixel_at(x, y), *other.pixel_at(x, y));
+                let color = match mode {
+                    BlendMode::Add => a + b,
+                    BlendMode::Multiply => a * b,
+                    BlendMode::Over(alpha) => a.lerp(b, alpha.clamp(0.0, 1.0)),
+                    BlendMode::Average => a.lerp(b, 0.5),
+                };
+                blended.write_pixel(x, y, color);
+            }
+        }
+        Ok(blended)
+    }
+
+    #[must_use]
+    pub fn flip_vertical(&self) -> Canvas {
+        self.remap(self.width, self.height, |x, y| (x, self.height - 1 - y))
+    }
+
+    #[must_use]
+    pub fn flip_horizontal(&self) -> Canvas {
+        self.remap(self.width, self.height, |x, y| (self.width - 1 - x, y))
+    }
+
+    // Rotates clockwise, so the top-left pixel ends up top-right.
+    #[must_use]
+    pub fn rotate_90(&self) -> Canvas {
+        self.remap(self.height, self.width, |x, y| (y, self.height - 1 - x))
+    }
+
+    fn remap(
+        &self,
+        width: usize,
+        height: usize,
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Canvas {
+        let mut remapped = Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let (sx, sy) = source(x, y);
+                remapped.write_pixel(x, y, *self.pixel_at(sx, sy));
+            }
+        }
+        remapped
+    }
+
+    #[must_use]
+    pub fn to_ansi(&self) -> String {
+        self.to_ansi_with(terminal_columns(), true_color_escape)
+    }
+
+    #[must_use]
+    pub fn to_ansi_256(&self) -> String {
+        self.to_ansi_with(terminal_columns(), palette_escape)
+    }
+
+    fn to_ansi_with(&self, columns: usize, escape: impl Fn(u8, (u8, u8, u8)) -> String) -> String {
+        let step = self.width.div_ceil(columns.max(1)).max(1);
+        let sample = |x: usize, y: usize| {
+            let pixel = self.pixel_at(x, y);
+            (to_byte(pixel.r), to_byte(pixel.g), to_byte(pixel.b))
+        };
+
+        let mut out = String::new();
+        for y in (0..self.height).step_by(2 * step) {
+            for x in (0..self.width).step_by(step) {
+                out.push_str(&escape(38, sample(x, y)));
+                if y + step < self.height {
+                    out.push_str(&escape(48, sample(x, y + step)));
+                }
+                out.push('\u{2580}');
+            }
+            out.push_str("\x1b[0m\n");
+        }
+        out
+    }
+
+    #[must_use]
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.width * self.height * 4);
+        for row in 0..self.height {
+            for cell in self.canvas.iter_row(row) {
+                bytes.push(to_byte(cell.r));
+                bytes.push(to_byte(cell.g));
+                bytes.push(to_byte(cell.b));
+                bytes.push(255);
+            }
+        }
+        bytes
+    }
+
+    #[cfg(test)]
+    #[must_use]
     fn to_ppm(&self) -> Vec<String> {
+        self.to_ppm_with(ClampMode::PerChannel)
+    }
+
+    #[cfg(test)]
+    #[must_use]
+    fn to_ppm_with(&self, mode: ClampMode) -> Vec<String> {
         let mut ppm = vec![
             "P3".to_string(),
             format!("{} {}", self.width, self.height),
@@ -44,18 +263,10 @@ impl Canvas {
         for row in 0..self.height {
             let mut row_buf = Vec::new();
             for cell in self.canvas.iter_row(row) {
-                row_buf.push(format!(
-                    "{}",
-                    (cell.r.clamp(0.0, 1.0) * 255.0).round() as isize
-                ));
-                row_buf.push(format!(
-                    "{}",
-                    (cell.g.clamp(0.0, 1.0) * 255.0).round() as isize
-                ));
-                row_buf.push(format!(
-                    "{}",
-                    (cell.b.clamp(0.0, 1.0) * 255.0).round() as isize
-                ));
+                let cell = mode.apply(*cell);
+                row_buf.push(format!("{}", to_byte(cell.r)));
+                row_buf.push(format!("{}", to_byte(cell.g)));
+                row_buf.push(format!("{}", to_byte(cell.b)));
             }
 
             let mut pixel_row = String::new();
@@ -76,19 +287,288 @@ impl Canvas {
         ppm
     }
 
+    #[allow(clippy::cast_possible_truncation)]
+    #[must_use]
+    pub fn to_pfm(&self) -> Vec<u8> {
+        let mut bytes = format!("PF\n{} {}\n-1.0\n", self.width, self.height).into_bytes();
+        bytes.reserve(self.width * self.height * 12);
+        for row in (0..self.height).rev() {
+            for cell in self.canvas.iter_row(row) {
+                for channel in [cell.r, cell.g, cell.b] {
+                    bytes.extend_from_slice(&(channel as f32).to_le_bytes());
+                }
+            }
+        }
+        bytes
+    }
+
     pub fn save(&self, path: &Path) {
-        let mut file = File::create(path).expect("create failed");
-        for line in &self.to_ppm() {
-            file.write_all(line.as_bytes()).expect("write failed");
-            file.write_all(b"\n").expect("write failed");
+        self.try_save(path).expect("save failed");
+    }
+
+    pub fn try_save(&self, path: &Path) -> Result<(), RaytracerError> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_ppm(&mut file)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    pub fn write_ppm(&self, w: &mut impl Write) -> io::Result<()> {
+        self.write_ppm_with(w, ClampMode::PerChannel)
+    }
+
+    pub fn write_ppm_with(&self, w: &mut impl Write, mode: ClampMode) -> io::Result<()> {
+        write!(w, "P3\n{} {}\n255\n", self.width, self.height)?;
+
+        for row in 0..self.height {
+            let mut line_len = 0;
+            for cell in self.canvas.iter_row(row) {
+                let cell = mode.apply(*cell);
+                for channel in [cell.r, cell.g, cell.b] {
+                    let byte = to_byte(channel);
+                    let len = match byte {
+                        100..=255 => 3,
+                        10..=99 => 2,
+                        _ => 1,
+                    };
+
+                    if line_len + len + 1 > 70 {
+                        w.write_all(b"\n")?;
+                        line_len = 0;
+                    }
+                    if line_len > 0 {
+                        w.write_all(b" ")?;
+                        line_len += 1;
+                    }
+                    write!(w, "{byte}")?;
+                    line_len += len;
+                }
+            }
+            w.write_all(b"\n")?;
         }
+        Ok(())
+    }
+}
+
+impl PartialEq for Canvas {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.canvas.iter().eq(other.canvas.iter())
     }
 }
 
+struct PpmReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> PpmReader<'a> {
+    fn token(&mut self) -> Result<&'a [u8], RaytracerError> {
+        loop {
+            match self.data.get(self.pos) {
+                Some(b'#') => {
+                    while !matches!(self.data.get(self.pos), None | Some(b'\n')) {
+                        self.pos += 1;
+                    }
+                }
+                Some(byte) if byte.is_ascii_whitespace() => self.pos += 1,
+                Some(_) => break,
+                None => return Err(RaytracerError::InvalidPpm),
+            }
+        }
+
+        let start = self.pos;
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|byte| !byte.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+        Ok(&self.data[start..self.pos])
+    }
+
+    fn number(&mut self) -> Result<usize, RaytracerError> {
+        std::str::from_utf8(self.token()?)
+            .ok()
+            .and_then(|token| token.parse().ok())
+            .ok_or(RaytracerError::InvalidPpm)
+    }
+
+    fn sample(&mut self, wide: bool) -> Result<usize, RaytracerError> {
+        let len = if wide { 2 } else { 1 };
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or(RaytracerError::InvalidPpm)?;
+        self.pos += len;
+        Ok(bytes
+            .iter()
+            .fold(0, |acc, &byte| (acc << 8) | usize::from(byte)))
+    }
+}
+
+fn terminal_columns() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(80)
+}
+
+fn true_color_escape(layer: u8, (r, g, b): (u8, u8, u8)) -> String {
+    format!("\x1b[{layer};2;{r};{g};{b}m")
+}
+
+// Nearest entry of the 6×6×6 color cube in the 256-color palette.
+fn palette_escape(layer: u8, (r, g, b): (u8, u8, u8)) -> String {
+    let level = |channel: u8| (u16::from(channel) * 5 + 127) / 255;
+    format!(
+        "\x1b[{layer};5;{}m",
+        16 + 36 * level(r) + 6 * level(g) + level(b)
+    )
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn to_byte(channel: f64) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn solid(width: usize, height: usize, color: Color) -> Canvas {
+        let mut c = Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                c.write_pixel(x, y, color);
+            }
+        }
+        c
+    }
+
+    #[test]
+    fn blend_canvases() {
+        let a = solid(3, 2, Color::new(0.2, 0.3, 0.4));
+        let b = solid(3, 2, Color::new(0.5, 0.5, 0.5));
+
+        assert_eq!(
+            a.blend(&b, BlendMode::Add).unwrap(),
+            solid(3, 2, Color::new(0.7, 0.8, 0.9))
+        );
+        assert_eq!(
+            solid(3, 2, Color::white())
+                .blend(&a, BlendMode::Multiply)
+                .unwrap(),
+            a
+        );
+        assert_eq!(a.blend(&b, BlendMode::Over(1.0)).unwrap(), b);
+        assert_eq!(
+            a.blend(&b, BlendMode::Average).unwrap(),
+            solid(3, 2, Color::new(0.35, 0.4, 0.45))
+        );
+    }
+
+    #[test]
+    fn flip_and_rotate() {
+        let red = Color::new(1.0, 0.0, 0.0);
+        let blue = Color::new(0.0, 0.0, 1.0);
+        let mut c = Canvas::new(2, 3);
+        c.write_pixel(0, 0, red);
+        c.write_pixel(1, 2, blue);
+
+        let flipped = c.flip_vertical();
+        assert_eq!(*flipped.pixel_at(0, 2), red);
+        assert_eq!(*flipped.pixel_at(1, 0), blue);
+        assert_eq!(flipped.flip_vertical(), c);
+
+        let mirrored = c.flip_horizontal();
+        assert_eq!(*mirrored.pixel_at(1, 0), red);
+        assert_eq!(*mirrored.pixel_at(0, 2), blue);
+
+        let rotated = c.rotate_90();
+        assert_eq!((rotated.width(), rotated.height()), (3, 2));
+        assert_eq!(*rotated.pixel_at(2, 0), red);
+        assert_eq!(*rotated.pixel_at(0, 1), blue);
+        assert_eq!(rotated.rotate_90().rotate_90().rotate_90(), c);
+    }
+
+    #[test]
+    fn blend_mismatched_sizes() {
+        assert!(matches!(
+            Canvas::new(3, 2).blend(&Canvas::new(2, 3), BlendMode::Add),
+            Err(RaytracerError::SizeMismatch {
+                expected: (3, 2),
+                found: (2, 3)
+            })
+        ));
+    }
+
+    #[test]
+    fn try_save_reports_io_errors() {
+        let path = std::env::temp_dir()
+            .join("raytracer_missing_dir")
+            .join("nested")
+            .join("img.ppm");
+
+        assert!(matches!(
+            Canvas::new(1, 1).try_save(&path),
+            Err(RaytracerError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn pfm_keeps_float_range() {
+        let mut c = Canvas::new(2, 3);
+        c.write_pixel(1, 2, Color::new(2.0, 0.5, 0.0));
+        let pfm = c.to_pfm();
+
+        let header: Vec<&str> = std::str::from_utf8(&pfm[..12]).unwrap().lines().collect();
+        assert_eq!(header, ["PF", "2 3", "-1.0"]);
+
+        let data = &pfm[12..];
+        assert_eq!(data.len(), 2 * 3 * 12);
+        let channel = |i: usize| {
+            f32::from_le_bytes([
+                data[i * 4],
+                data[i * 4 + 1],
+                data[i * 4 + 2],
+                data[i * 4 + 3],
+            ])
+        };
+        assert_eq!([channel(3), channel(4), channel(5)], [2.0, 0.5, 0.0]);
+        assert_eq!([channel(0), channel(1), channel(2)], [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn ansi_preview() {
+        let mut c = Canvas::new(2, 2);
+        for x in 0..2 {
+            for y in 0..2 {
+                c.write_pixel(x, y, Color::new(1.0, 0.0, 0.0));
+            }
+        }
+
+        let ansi = c.to_ansi_with(80, true_color_escape);
+        assert!(ansi.contains("\x1b[38;2;255;0;0m"));
+        assert!(ansi.contains("\x1b[48;2;255;0;0m"));
+        assert_eq!(ansi.matches('\u{2580}').count(), 2);
+        assert!(c
+            .to_ansi_with(80, palette_escape)
+            .contains("\x1b[38;5;196m"));
+        assert_eq!(
+            c.to_ansi_with(1, true_color_escape)
+                .matches('\u{2580}')
+                .count(),
+            1
+        );
+
+        let single = Canvas::new(1, 1).to_ansi_with(80, true_color_escape);
+        assert!(single.contains("\x1b[38;2;0;0;0m"));
+        assert!(!single.contains("48;2"));
+    }
+
     #[test]
     fn new_canvas() {
         let canvas = Canvas::new(10, 20);
@@ -128,6 +608,32 @@ mod tests {
         assert_eq!(ppm[5], String::from("0 0 0 0 0 0 0 0 0 0 0 0 0 0 255"));
     }
 
+    #[test]
+    fn ppm_clamp_modes() {
+        let mut c = Canvas::new(2, 1);
+        c.write_pixel(0, 0, Color::new(2.0, 1.0, 0.5));
+        c.write_pixel(1, 0, Color::new(0.0, 0.5, 0.0));
+
+        assert_eq!(
+            ClampMode::PreserveHue.apply(Color::new(2.0, 1.0, 0.5)),
+            Color::new(1.0, 0.5, 0.25)
+        );
+        assert_eq!(c.to_ppm_with(ClampMode::PerChannel), c.to_ppm());
+        assert_eq!(c.to_ppm()[3], "255 255 128 0 128 0");
+        assert_eq!(
+            c.to_ppm_with(ClampMode::PreserveHue)[3],
+            "255 128 64 0 128 0"
+        );
+
+        let mut bytes = Vec::new();
+        c.write_ppm_with(&mut bytes, ClampMode::PreserveHue)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            c.to_ppm_with(ClampMode::PreserveHue).join("\n")
+        );
+    }
+
     #[test]
     fn ppm_long_lines() {
         let mut c = Canvas::new(10, 2);
@@ -156,10 +662,101 @@ mod tests {
         );
     }
 
+    #[test]
+    fn write_ppm_matches_to_ppm() {
+        let mut c = Canvas::new(10, 2);
+        for x in 0..10 {
+            for y in 0..2 {
+                c.write_pixel(x, y, Color::new(1.0, 0.8, 0.6));
+            }
+        }
+
+        let mut bytes = Vec::new();
+        c.write_ppm(&mut bytes).unwrap();
+
+        assert_eq!(String::from_utf8(bytes).unwrap(), c.to_ppm().join("\n"));
+    }
+
     #[test]
     fn ppm_newline_at_end() {
         let c = Canvas::new(3, 2);
         let ppm = c.to_ppm();
         assert_eq!(ppm[5], String::new());
     }
+
+    #[test]
+    fn find_invalid_pixels() {
+        let mut c = Canvas::new(4, 3);
+        assert!(c.find_invalid().is_empty());
+
+        c.write_pixel(2, 1, Color::new(f64::NAN, 0.0, 0.0));
+        c.write_pixel(3, 2, Color::new(0.0, 0.0, f64::INFINITY));
+        assert_eq!(c.find_invalid(), vec![(2, 1), (3, 2)]);
+    }
+
+    #[test]
+    fn ppm_round_trip() {
+        let mut c = Canvas::new(3, 2);
+        c.write_pixel(0, 0, Color::new(1.0, 0.0, 0.0));
+        c.write_pixel(2, 1, Color::new(0.0, 0.2, 1.0));
+        let mut bytes = Vec::new();
+        c.write_ppm(&mut bytes).unwrap();
+
+        let read = Canvas::from_ppm(&bytes, false).unwrap();
+        assert_eq!((read.width(), read.height()), (3, 2));
+        assert_eq!(*read.pixel_at(0, 0), Color::new(1.0, 0.0, 0.0));
+        assert_eq!(*read.pixel_at(2, 1), Color::new(0.0, 0.2, 1.0));
+
+        let raw = b"P6\n# comment\n2 1\n255\n\xff\x00\x00\x00\x00\xff";
+        let read = Canvas::from_ppm(raw, false).unwrap();
+        assert_eq!(*read.pixel_at(1, 0), Color::new(0.0, 0.0, 1.0));
+
+        assert!(matches!(
+            Canvas::from_ppm(b"P3\n2 1\n255\n0 0 0", false),
+            Err(RaytracerError::InvalidPpm)
+        ));
+        assert!(Canvas::from_ppm(b"P3\n1 1\n255\n0 0 300", false).is_err());
+    }
+
+    #[test]
+    fn ppm_oversized_header() {
+        for data in [
+            &b"P3\n4000000000 4000000000\n255\n0 0 0\n"[..],
+            b"P6\n18446744073709551615 2\n255\n\x00\x00\x00",
+            b"P6\n2 1\n65535\n\x00\x00\x00\x00\x00\x00",
+        ] {
+            assert!(matches!(
+                Canvas::from_ppm(data, false),
+                Err(RaytracerError::InvalidPpm)
+            ));
+        }
+    }
+
+    #[test]
+    fn ppm_linearize() {
+        let gray = b"P3\n1 1\n255\n128 128 128\n";
+
+        let raw = Canvas::from_ppm(gray, false).unwrap();
+        assert!(raw
+            .pixel_at(0, 0)
+            .approx_eq(&Color::new(0.502, 0.502, 0.502), 1e-3));
+
+        let linear = Canvas::from_ppm(gray, true).unwrap();
+        assert!(linear
+            .pixel_at(0, 0)
+            .approx_eq(&Color::new(0.216, 0.216, 0.216), 1e-3));
+    }
+
+    #[test]
+    fn rgba8_bytes() {
+        let mut c = Canvas::new(2, 2);
+        c.write_pixel(1, 0, Color::new(1.5, 0.5, -0.5));
+        c.write_pixel(0, 1, Color::new(0.0, 0.0, 1.0));
+        let bytes = c.to_rgba8();
+
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(bytes[0..4], [0, 0, 0, 255]);
+        assert_eq!(bytes[4..8], [255, 128, 0, 255]);
+        assert_eq!(bytes[8..12], [0, 0, 255, 255]);
+    }
 }
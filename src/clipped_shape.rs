@@ -0,0 +1,234 @@
+use crate::shape::next_id;
+use crate::transformations::Transformable;
+use crate::utils::EPSILON;
+use crate::{BoundingBox, Intersection, Material, Matrix, Object, Point, Ray, Shape, Vector};
+
+// The clip planes live in the wrapper's object space, which is world space
+// only while the wrapper has the identity transform and is not nested; they
+// move along with `transform` but not with the inner shape's own transform.
+#[derive(Debug, Clone)]
+pub struct ClippedShape {
+    pub transform: Matrix,
+    pub inner: Box<Object>,
+    pub planes: Vec<(Point, Vector)>,
+    pub(crate) id: usize,
+}
+
+impl ClippedShape {
+    #[must_use]
+    pub fn new(inner: Object, planes: Vec<(Point, Vector)>) -> Self {
+        Self {
+            transform: Matrix::default(),
+            inner: Box::new(inner),
+            planes,
+            id: next_id(),
+        }
+    }
+
+    #[must_use]
+    pub fn keeps(&self, point: Point) -> bool {
+        self.planes
+            .iter()
+            .all(|(origin, normal)| (point - *origin).dot(normal) >= -EPSILON)
+    }
+}
+
+impl PartialEq for ClippedShape {
+    fn eq(&self, other: &Self) -> bool {
+        self.transform == other.transform
+            && self.inner == other.inner
+            && self.planes == other.planes
+    }
+}
+
+impl Default for ClippedShape {
+    fn default() -> Self {
+        Self::new(Object::default(), Vec::new())
+    }
+}
+
+impl Transformable for ClippedShape {
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Shape for ClippedShape {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn get_material(&self) -> Material {
+        self.inner.get_material()
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.inner.set_material(material);
+    }
+
+    // Hits keep the inner object that was struck, so it shades itself, but
+    // take the wrapper's id so refraction sees one object (as mesh faces do).
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection> {
+        ray.intersect(self.inner.as_ref())
+            .into_iter()
+            .filter(|i| self.keeps(ray.position(i.t)))
+            .map(|mut i| {
+                i.object
+                    .set_transform(self.transform * i.object.get_transform());
+                i.object.assign_ids(self.id);
+                i
+            })
+            .collect()
+    }
+
+    fn local_normal_at(&self, point: Point) -> Vector {
+        let inner_point = self.inner.world_to_object(point);
+        self.inner
+            .normal_to_world(self.inner.local_normal_at(inner_point))
+    }
+
+    fn local_normal_at_uv(&self, point: Point, u: f64, v: f64) -> Vector {
+        let inner_point = self.inner.world_to_object(point);
+        self.inner
+            .normal_to_world(self.inner.local_normal_at_uv(inner_point, u, v))
+    }
+
+    fn local_face_normal_at(&self, point: Point) -> Vector {
+        let inner_point = self.inner.world_to_object(point);
+        self.inner
+            .normal_to_world(self.inner.local_face_normal_at(inner_point))
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.inner.parent_space_bounds()
+    }
+
+    fn uv_at(&self, point: Point) -> Option<(f64, f64)> {
+        self.inner.uv_at(self.inner.world_to_object(point))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::equal;
+    use crate::{vector, Mesh, Sphere};
+
+    #[test]
+    fn clip_sphere_near_hemisphere() {
+        let clipped = ClippedShape::new(
+            Object::Sphere(Sphere::default()),
+            vec![(Point::default(), -vector::Z)],
+        );
+
+        let xs = clipped.local_intersect(&Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z));
+        assert_eq!(xs.len(), 1);
+        assert!(equal(xs[0].t, 4.0));
+
+        let xs = clipped.local_intersect(&Ray::new(Point::new(0.0, 0.0, 5.0), -vector::Z));
+        assert_eq!(xs.len(), 1);
+        assert!(equal(xs[0].t, 6.0));
+    }
+
+    #[test]
+    fn clip_keeps_points_on_plane() {
+        let clipped = ClippedShape::new(
+            Object::Sphere(Sphere::default()),
+            vec![(Point::default(), -vector::Z)],
+        );
+
+        assert!(clipped.keeps(Point::new(0.0, 1.0, EPSILON / 2.0)));
+        assert!(!clipped.keeps(Point::new(0.0, 1.0, 0.01)));
+
+        let xs = clipped.local_intersect(&Ray::new(Point::new(0.0, 5.0, 0.0), -vector::Y));
+        assert_eq!(xs.len(), 2);
+    }
+
+    #[test]
+    fn clipped_intersections_carry_transform() {
+        let mut clipped = ClippedShape::new(
+            Object::Sphere(Sphere::default()),
+            vec![(Point::default(), -vector::Z)],
+        );
+        clipped.set_transform(Matrix::translation(Vector::new(0.0, 0.0, 10.0)));
+        let object = Object::Clipped(clipped);
+
+        let ray = Ray::new(Point::new(0.0, 0.0, 5.0), vector::Z);
+        let xs = ray.intersect(&object);
+        assert_eq!(xs.len(), 1);
+        assert!(equal(xs[0].t, 4.0));
+        assert_eq!(xs[0].object.normal_at(ray.position(xs[0].t)), -vector::Z);
+    }
+
+    #[test]
+    fn clipped_intersections_carry_the_clipped_id() {
+        let object = Object::Clipped(ClippedShape::new(
+            Object::Sphere(Sphere::default()),
+            vec![(Point::default(), -vector::Z)],
+        ));
+
+        let xs = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z).intersect(&object);
+        assert_eq!(xs.len(), 1);
+        assert!(matches!(xs[0].object, Object::Sphere(_)));
+        assert!(xs[0].object.same_object(&object));
+        assert!(object.includes(&xs[0].object));
+    }
+
+    #[test]
+    fn clipped_mesh_shades_the_struck_face() {
+        let mesh = Mesh::new(
+            vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(1.0, 1.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+            ],
+            vec![[0, 1, 2], [0, 2, 3]],
+        );
+        let mut clipped =
+            ClippedShape::new(Object::Mesh(mesh), vec![(Point::default(), vector::X)]);
+        clipped.set_transform(Matrix::translation(Vector::new(0.0, 0.0, 5.0)));
+        let object = Object::Clipped(clipped);
+
+        let ray = Ray::new(Point::new(0.25, 0.75, 0.0), vector::Z);
+        let xs = ray.intersect(&object);
+        assert_eq!(xs.len(), 1);
+        assert!(matches!(xs[0].object, Object::SmoothTriangle(_)));
+        assert_eq!(
+            xs[0].object.normal_at_uv(ray.position(xs[0].t), xs[0].uv),
+            vector::Z
+        );
+        assert!(xs[0].object.same_object(&object));
+    }
+
+    #[test]
+    fn transformed_inner_shape() {
+        let inner = Object::Sphere(Sphere::new(
+            Matrix::translation(Vector::new(0.0, 0.0, 2.0))
+                * Matrix::scaling(Vector::new(2.0, 2.0, 2.0)),
+            Material::default(),
+        ));
+        let mut clipped = ClippedShape::new(inner, vec![(Point::default(), -vector::Z)]);
+        clipped.set_transform(Matrix::translation(Vector::new(1.0, 0.0, 0.0)));
+        let object = Object::Clipped(clipped);
+
+        // The sphere spans z in [0, 4] and x in [-1, 3]; only its rim at
+        // z = 0 survives the plane.
+        let ray = Ray::new(Point::new(1.0, 0.0, -5.0), vector::Z);
+        let xs = ray.intersect(&object);
+        assert_eq!(xs.len(), 1);
+        assert!(equal(xs[0].t, 5.0));
+        assert_eq!(xs[0].object.normal_at(ray.position(xs[0].t)), -vector::Z);
+
+        let side = Point::new(3.0, 0.0, 2.0);
+        assert_eq!(object.normal_at(side), vector::X);
+        assert_eq!(object.face_normal_at(side), vector::X);
+
+        let (u, v) = object.uv_at(object.world_to_object(side)).unwrap();
+        assert!(equal(u, 0.25) && equal(v, 0.5));
+    }
+}
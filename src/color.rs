@@ -1,5 +1,6 @@
-use crate::utils::equal;
+use crate::utils::{equal_rel, equal_within, EPSILON};
 
+use std::fmt;
 use std::ops::{Add, Mul, Sub};
 
 #[derive(Debug, Clone, Copy)]
@@ -24,6 +25,47 @@ impl Color {
     pub fn black() -> Self {
         Self::new(0.0, 0.0, 0.0)
     }
+
+    #[must_use]
+    pub fn lerp(&self, other: Self, t: f64) -> Self {
+        *self + (other - *self) * t
+    }
+
+    #[must_use]
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
+    #[must_use]
+    pub fn is_finite(&self) -> bool {
+        self.r.is_finite() && self.g.is_finite() && self.b.is_finite()
+    }
+
+    #[must_use]
+    pub fn srgb_to_linear(&self) -> Self {
+        let decode = |c: f64| {
+            if c > 0.04045 {
+                ((c + 0.055) / 1.055).powf(2.4)
+            } else {
+                c / 12.92
+            }
+        };
+        Self::new(decode(self.r), decode(self.g), decode(self.b))
+    }
+
+    #[must_use]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        equal_within(self.r, other.r, epsilon)
+            && equal_within(self.g, other.g, epsilon)
+            && equal_within(self.b, other.b, epsilon)
+    }
+
+    #[must_use]
+    pub fn approx_eq_rel(&self, other: &Self, abs_tol: f64, rel_tol: f64) -> bool {
+        equal_rel(self.r, other.r, abs_tol, rel_tol)
+            && equal_rel(self.g, other.g, abs_tol, rel_tol)
+            && equal_rel(self.b, other.b, abs_tol, rel_tol)
+    }
 }
 
 impl Default for Color {
@@ -32,9 +74,15 @@ impl Default for Color {
     }
 }
 
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "rgb({}, {}, {})", self.r, self.g, self.b)
+    }
+}
+
 impl PartialEq for Color {
     fn eq(&self, other: &Self) -> bool {
-        equal(self.r, other.r) && equal(self.g, other.g) && equal(self.b, other.b)
+        self.approx_eq(other, EPSILON)
     }
 }
 
@@ -89,6 +137,12 @@ impl Mul for Color {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::equal;
+
+    #[test]
+    fn display_color() {
+        assert_eq!(Color::new(1.0, 0.5, 0.0).to_string(), "rgb(1, 0.5, 0)");
+    }
 
     #[test]
     fn new_color() {
@@ -123,4 +177,13 @@ mod tests {
         let c2 = Color::new(0.9, 1.0, 0.1);
         assert_eq!(c1 * c2, Color::new(0.9, 0.2, 0.04));
     }
+
+    #[test]
+    fn color_lerp() {
+        let c1 = Color::new(1.0, 0.2, 0.4);
+        let c2 = Color::new(0.0, 1.0, 0.4);
+        assert_eq!(c1.lerp(c2, 0.0), c1);
+        assert_eq!(c1.lerp(c2, 1.0), c2);
+        assert_eq!(c1.lerp(c2, 0.25), Color::new(0.75, 0.4, 0.4));
+    }
 }
@@ -0,0 +1,287 @@
+use crate::shape::next_id;
+use crate::transformations::Transformable;
+use crate::utils::EPSILON;
+use crate::{BoundingBox, Intersection, Material, Matrix, Object, Point, Ray, Shape, Vector};
+
+#[derive(Debug, Clone)]
+pub struct Cone {
+    transform: Matrix,
+    material: Material,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+    pub(crate) id: usize,
+}
+
+impl Cone {
+    #[must_use]
+    pub fn truncated(minimum: f64, maximum: f64, closed: bool) -> Self {
+        Self {
+            minimum,
+            maximum,
+            closed,
+            ..Self::default()
+        }
+    }
+
+    // A cap at height `y` has radius `|y|`.
+    fn check_cap(ray: &Ray, t: f64, y: f64) -> bool {
+        let x = ray.origin.x + t * ray.direction.x;
+        let z = ray.origin.z + t * ray.direction.z;
+        x * x + z * z <= y * y + EPSILON
+    }
+
+    fn intersect_caps(&self, ray: &Ray, xs: &mut Vec<Intersection>) {
+        if !self.closed || ray.direction.y.abs() < EPSILON {
+            return;
+        }
+
+        for y in [self.minimum, self.maximum] {
+            // Unbounded ends have no cap to hit.
+            if !y.is_finite() {
+                continue;
+            }
+            let t = (y - ray.origin.y) / ray.direction.y;
+            if Self::check_cap(ray, t, y) {
+                xs.push(Intersection::new(t, &Object::Cone(self.clone())));
+            }
+        }
+    }
+
+    fn push_if_within(&self, ray: &Ray, t: f64, xs: &mut Vec<Intersection>) {
+        let y = ray.origin.y + t * ray.direction.y;
+        if self.minimum < y && y < self.maximum {
+            xs.push(Intersection::new(t, &Object::Cone(self.clone())));
+        }
+    }
+}
+
+impl Default for Cone {
+    fn default() -> Self {
+        Self {
+            transform: Matrix::default(),
+            material: Material::default(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+            id: next_id(),
+        }
+    }
+}
+
+impl PartialEq for Cone {
+    fn eq(&self, other: &Self) -> bool {
+        self.transform == other.transform
+            && self.material == other.material
+            && self.minimum == other.minimum
+            && self.maximum == other.maximum
+            && self.closed == other.closed
+    }
+}
+
+impl Transformable for Cone {
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Shape for Cone {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn get_material(&self) -> Material {
+        self.material.clone()
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection> {
+        let (origin, direction) = (ray.origin, ray.direction);
+        let a = direction.x * direction.x - direction.y * direction.y + direction.z * direction.z;
+        let b = 2.0 * origin.x * direction.x - 2.0 * origin.y * direction.y
+            + 2.0 * origin.z * direction.z;
+        let c = origin.x * origin.x - origin.y * origin.y + origin.z * origin.z;
+
+        let mut xs = Vec::new();
+        if a.abs() < EPSILON {
+            // Parallel to one of the halves: at most one wall hit.
+            if b.abs() >= EPSILON {
+                self.push_if_within(ray, -c / (2.0 * b), &mut xs);
+            }
+        } else {
+            let discriminant = b * b - 4.0 * a * c;
+            if discriminant < -EPSILON {
+                return xs;
+            }
+
+            let root = discriminant.max(0.0).sqrt();
+            let (t0, t1) = ((-b - root) / (2.0 * a), (-b + root) / (2.0 * a));
+            self.push_if_within(ray, t0.min(t1), &mut xs);
+            self.push_if_within(ray, t0.max(t1), &mut xs);
+        }
+
+        self.intersect_caps(ray, &mut xs);
+        xs
+    }
+
+    fn local_normal_at(&self, point: Point) -> Vector {
+        let dist = point.x * point.x + point.z * point.z;
+
+        if dist < self.maximum * self.maximum && point.y >= self.maximum - EPSILON {
+            Vector::new(0.0, 1.0, 0.0)
+        } else if dist < self.minimum * self.minimum && point.y <= self.minimum + EPSILON {
+            Vector::new(0.0, -1.0, 0.0)
+        } else {
+            let y = if point.y > 0.0 {
+                -dist.sqrt()
+            } else {
+                dist.sqrt()
+            };
+            // The apex has no slope; point along the axis instead.
+            if y.abs() < EPSILON {
+                return Vector::new(0.0, if point.y > 0.0 { -1.0 } else { 1.0 }, 0.0);
+            }
+            Vector::new(point.x, y, point.z)
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let radius = self.minimum.abs().max(self.maximum.abs());
+        BoundingBox::new(
+            Point::new(-radius, self.minimum, -radius),
+            Point::new(radius, self.maximum, radius),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::equal;
+
+    #[test]
+    fn ray_intersects_cone() {
+        let cone = Cone::default();
+        let cases = [
+            (
+                Point::new(0.0, 0.0, -5.0),
+                Vector::new(0.0, 0.0, 1.0),
+                5.0,
+                5.0,
+            ),
+            (
+                Point::new(0.0, 0.0, -5.0),
+                Vector::new(1.0, 1.0, 1.0),
+                8.66025,
+                8.66025,
+            ),
+            (
+                Point::new(1.0, 1.0, -5.0),
+                Vector::new(-0.5, -1.0, 1.0),
+                4.55006,
+                49.44994,
+            ),
+        ];
+
+        for (origin, direction, t0, t1) in cases {
+            let xs = cone.local_intersect(&Ray::new(origin, direction.normalize()));
+            assert_eq!(xs.len(), 2);
+            assert!(equal(xs[0].t, t0));
+            assert!(equal(xs[1].t, t1));
+        }
+    }
+
+    #[test]
+    fn normal_at_apex() {
+        assert_eq!(
+            Object::Cone(Cone::default()).normal_at(Point::default()),
+            Vector::new(0.0, 1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn closed_unbounded_cone_has_no_caps() {
+        let cone = Cone {
+            closed: true,
+            ..Cone::default()
+        };
+        let xs = cone.local_intersect(&Ray::new(
+            Point::new(0.5, -5.0, 0.0),
+            Vector::new(0.1, 1.0, 0.1).normalize(),
+        ));
+
+        assert!(!xs.is_empty());
+        assert!(xs.iter().all(|i| i.t.is_finite()));
+    }
+
+    #[test]
+    fn ray_parallel_to_one_half() {
+        let cone = Cone::default();
+        let r = Ray::new(
+            Point::new(0.0, 0.0, -1.0),
+            Vector::new(0.0, 1.0, 1.0).normalize(),
+        );
+        let xs = cone.local_intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert!(equal(xs[0].t, 0.35355));
+    }
+
+    #[test]
+    fn capped_cone() {
+        let cone = Cone::truncated(-0.5, 0.5, true);
+        let cases = [
+            (Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0), 0),
+            (Point::new(0.0, 0.0, -0.25), Vector::new(0.0, 1.0, 1.0), 2),
+            (Point::new(0.0, 0.0, -0.25), Vector::new(0.0, 1.0, 0.0), 4),
+        ];
+
+        for (origin, direction, count) in cases {
+            let r = Ray::new(origin, direction.normalize());
+            assert_eq!(cone.local_intersect(&r).len(), count);
+        }
+    }
+
+    #[test]
+    fn normals() {
+        let cone = Cone::default();
+        let cases = [
+            (Point::new(0.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0)),
+            (
+                Point::new(1.0, 1.0, 1.0),
+                Vector::new(1.0, -(2_f64.sqrt()), 1.0),
+            ),
+            (Point::new(-1.0, -1.0, 0.0), Vector::new(-1.0, 1.0, 0.0)),
+        ];
+
+        for (point, normal) in cases {
+            assert_eq!(cone.local_normal_at(point), normal);
+        }
+
+        let capped = Cone::truncated(-1.0, 2.0, true);
+        assert_eq!(
+            capped.local_normal_at(Point::new(0.5, 2.0, 0.0)),
+            Vector::new(0.0, 1.0, 0.0)
+        );
+        assert_eq!(
+            capped.local_normal_at(Point::new(0.0, -1.0, 0.5)),
+            Vector::new(0.0, -1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn cone_bounds() {
+        assert!(!Cone::default().bounds().is_finite());
+        assert_eq!(
+            Cone::truncated(-5.0, 3.0, false).bounds(),
+            BoundingBox::new(Point::new(-5.0, -5.0, -5.0), Point::new(5.0, 3.0, 5.0))
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use crate::shape::next_id;
+use crate::transformations::Transformable;
+use crate::{BoundingBox, Intersection, Material, Matrix, Object, Point, Ray, Shape, Vector};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl CsgOperation {
+    #[must_use]
+    pub fn allows(self, left_hit: bool, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => (left_hit && !in_right) || (!left_hit && !in_left),
+            CsgOperation::Intersection => (left_hit && in_right) || (!left_hit && in_left),
+            CsgOperation::Difference => (left_hit && !in_right) || (!left_hit && in_left),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Csg {
+    pub transform: Matrix,
+    pub operation: CsgOperation,
+    pub left: Box<Object>,
+    pub right: Box<Object>,
+    pub(crate) id: usize,
+}
+
+impl Csg {
+    #[must_use]
+    pub fn new(operation: CsgOperation, left: Object, right: Object) -> Self {
+        Self {
+            transform: Matrix::default(),
+            operation,
+            left: Box::new(left),
+            right: Box::new(right),
+            id: next_id(),
+        }
+    }
+
+    // Expects `xs` sorted by t; walks them tracking whether we are inside
+    // each operand.
+    #[must_use]
+    pub fn filter_intersections(&self, xs: Vec<Intersection>) -> Vec<Intersection> {
+        let (mut in_left, mut in_right) = (false, false);
+        let mut result = Vec::new();
+
+        for i in xs {
+            let left_hit = self.left.includes(&i.object);
+            if self.operation.allows(left_hit, in_left, in_right) {
+                result.push(i);
+            }
+
+            if left_hit {
+                in_left = !in_left;
+            } else {
+                in_right = !in_right;
+            }
+        }
+        result
+    }
+}
+
+impl PartialEq for Csg {
+    fn eq(&self, other: &Self) -> bool {
+        self.transform == other.transform
+            && self.operation == other.operation
+            && self.left == other.left
+            && self.right == other.right
+    }
+}
+
+impl Default for Csg {
+    fn default() -> Self {
+        Self::new(CsgOperation::Union, Object::default(), Object::default())
+    }
+}
+
+impl Transformable for Csg {
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Shape for Csg {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn get_material(&self) -> Material {
+        self.left.get_material()
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.left.set_material(material.clone());
+        self.right.set_material(material);
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection> {
+        if !self.bounds().intersects_ray(ray) {
+            return Vec::new();
+        }
+
+        let mut xs = ray.intersect(self.left.as_ref());
+        xs.extend(ray.intersect(self.right.as_ref()));
+        xs.sort_by(|a, b| a.t.total_cmp(&b.t));
+
+        self.filter_intersections(xs)
+            .into_iter()
+            .map(|mut i| {
+                i.object
+                    .set_transform(self.transform * i.object.get_transform());
+                i
+            })
+            .collect()
+    }
+
+    fn local_normal_at(&self, point: Point) -> Vector {
+        self.left.local_normal_at(point)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.left
+            .parent_space_bounds()
+            .merge(&self.right.parent_space_bounds())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::equal;
+    use crate::{vector, Cube, Sphere};
+
+    #[test]
+    fn operation_rules() {
+        use CsgOperation::{Difference, Intersection as Intersect, Union};
+
+        let cases = [
+            (Union, [false, true, false, true, false, false, true, true]),
+            (
+                Intersect,
+                [true, false, true, false, true, true, false, false],
+            ),
+            (
+                Difference,
+                [false, true, false, true, true, true, false, false],
+            ),
+        ];
+        for (operation, expected) in cases {
+            let mut index = 0;
+            for left_hit in [true, false] {
+                for in_left in [true, false] {
+                    for in_right in [true, false] {
+                        assert_eq!(
+                            operation.allows(left_hit, in_left, in_right),
+                            expected[index],
+                            "{operation:?} {left_hit} {in_left} {in_right}"
+                        );
+                        index += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn filtering_intersections() {
+        let s1 = Object::Sphere(Sphere::default());
+        let s2 = Object::Cube(Cube::default());
+        let xs = vec![
+            Intersection::new(1.0, &s1),
+            Intersection::new(2.0, &s2),
+            Intersection::new(3.0, &s1),
+            Intersection::new(4.0, &s2),
+        ];
+
+        for (operation, first, second) in [
+            (CsgOperation::Union, 0, 3),
+            (CsgOperation::Intersection, 1, 2),
+            (CsgOperation::Difference, 0, 1),
+        ] {
+            let csg = Csg::new(operation, s1.clone(), s2.clone());
+            let result = csg.filter_intersections(xs.clone());
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0], xs[first]);
+            assert_eq!(result[1], xs[second]);
+        }
+    }
+
+    #[test]
+    fn ray_misses_csg() {
+        let csg = Csg::new(
+            CsgOperation::Union,
+            Object::Sphere(Sphere::default()),
+            Object::Cube(Cube::default()),
+        );
+        let r = Ray::new(Point::new(0.0, 2.0, -5.0), vector::Z);
+        assert!(csg.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn ray_hits_csg() {
+        let s1 = Object::Sphere(Sphere::default());
+        let s2 = Object::Sphere(Sphere::new(
+            Matrix::translation(Vector::new(0.0, 0.0, 0.5)),
+            Material::default(),
+        ));
+        let csg = Csg::new(CsgOperation::Union, s1.clone(), s2.clone());
+
+        let xs = csg.local_intersect(&Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z));
+        assert_eq!(xs.len(), 2);
+        assert!(equal(xs[0].t, 4.0));
+        assert!(xs[0].object.same_object(&s1));
+        assert!(equal(xs[1].t, 6.5));
+        assert!(xs[1].object.same_object(&s2));
+    }
+
+    #[test]
+    fn csg_bounds() {
+        let csg = Csg::new(
+            CsgOperation::Difference,
+            Object::Cube(Cube::default()),
+            Object::Sphere(Sphere::new(
+                Matrix::translation(Vector::new(2.0, 0.0, 0.0)),
+                Material::default(),
+            )),
+        );
+
+        assert_eq!(
+            csg.bounds(),
+            BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(3.0, 1.0, 1.0))
+        );
+        assert!(!csg
+            .bounds()
+            .intersects_ray(&Ray::new(Point::new(0.0, 2.0, -5.0), vector::Z)));
+    }
+
+    #[test]
+    fn cube_minus_sphere() {
+        let sphere = Object::Sphere(Sphere::new(
+            Matrix::scaling(Vector::new(1.3, 1.3, 1.3)),
+            Material::default(),
+        ));
+        let mut csg = Object::Csg(Csg::new(
+            CsgOperation::Difference,
+            Object::Cube(Cube::default()),
+            sphere.clone(),
+        ));
+        csg.set_transform(Matrix::translation(Vector::new(0.0, 0.0, 10.0)));
+
+        // Straight through the middle the sphere carves the cube away entirely.
+        let r = Ray::new(Point::new(0.0, 0.0, 0.0), vector::Z);
+        assert!(r.intersect(&csg).is_empty());
+
+        // Near a corner the ray enters the cube, then exits into the hollow.
+        let r = Ray::new(Point::new(0.8, 0.8, 0.0), vector::Z);
+        let xs = r.intersect(&csg);
+        assert_eq!(xs.len(), 4);
+        assert!(equal(xs[0].t, 9.0));
+        assert!(xs[1].object.same_object(&sphere));
+        let point = r.position(xs[1].t);
+        assert_eq!(
+            xs[1].object.normal_at(point),
+            (point - Point::new(0.0, 0.0, 10.0)).normalize()
+        );
+    }
+}
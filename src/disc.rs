@@ -0,0 +1,130 @@
+use crate::shape::next_id;
+use crate::transformations::Transformable;
+use crate::utils::EPSILON;
+use crate::{
+    vector, BoundingBox, Intersection, Material, Matrix, Object, Point, Ray, Shape, Vector,
+};
+
+// Unit disc in the xz-plane; scale it for other radii.
+#[derive(Debug, Clone)]
+pub struct Disc {
+    transform: Matrix,
+    material: Material,
+    pub inner_radius: f64,
+    pub(crate) id: usize,
+}
+
+impl Disc {
+    #[must_use]
+    pub fn annulus(inner_radius: f64) -> Self {
+        Self {
+            inner_radius,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for Disc {
+    fn default() -> Self {
+        Self {
+            transform: Matrix::default(),
+            material: Material::default(),
+            inner_radius: 0.0,
+            id: next_id(),
+        }
+    }
+}
+
+impl PartialEq for Disc {
+    fn eq(&self, other: &Self) -> bool {
+        self.transform == other.transform
+            && self.material == other.material
+            && self.inner_radius == other.inner_radius
+    }
+}
+
+impl Transformable for Disc {
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Shape for Disc {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn get_material(&self) -> Material {
+        self.material.clone()
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection> {
+        if ray.direction.y.abs() < EPSILON {
+            return Vec::new();
+        }
+
+        let t = -ray.origin.y / ray.direction.y;
+        let x = ray.origin.x + t * ray.direction.x;
+        let z = ray.origin.z + t * ray.direction.z;
+        let dist = x * x + z * z;
+        if dist > 1.0 || dist < self.inner_radius * self.inner_radius {
+            return Vec::new();
+        }
+
+        vec![Intersection::new(t, &Object::Disc(self.clone()))]
+    }
+
+    fn local_normal_at(&self, _point: Point) -> Vector {
+        vector::Y
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(Point::new(-1.0, 0.0, -1.0), Point::new(1.0, 0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::equal;
+
+    #[test]
+    fn ray_intersects_disc() {
+        let disc = Disc::default();
+        let cases = [
+            (Point::new(0.0, 1.0, 0.0), -vector::Y, Some(1.0)),
+            (Point::new(0.9, -2.0, 0.0), vector::Y, Some(2.0)),
+            (Point::new(0.8, 1.0, 0.8), -vector::Y, None),
+            (Point::new(0.0, 1.0, 0.0), vector::Z, None),
+        ];
+
+        for (origin, direction, t) in cases {
+            let xs = disc.local_intersect(&Ray::new(origin, direction));
+            assert_eq!(xs.first().map(|i| i.t), t);
+        }
+    }
+
+    #[test]
+    fn annulus_has_a_hole() {
+        let annulus = Disc::annulus(0.5);
+        let hole = Ray::new(Point::new(0.25, 1.0, 0.0), -vector::Y);
+        assert!(annulus.local_intersect(&hole).is_empty());
+
+        let ring = Ray::new(Point::new(0.0, 1.0, -0.75), -vector::Y);
+        let xs = annulus.local_intersect(&ring);
+        assert_eq!(xs.len(), 1);
+        assert!(equal(xs[0].t, 1.0));
+        assert_eq!(
+            annulus.local_normal_at(Point::new(0.0, 0.0, -0.75)),
+            vector::Y
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum RaytracerError {
+    SingularMatrix,
+    BadDimension {
+        dimension: usize,
+        len: usize,
+    },
+    UninitializedPattern,
+    DegenerateNormal,
+    InvalidPpm,
+    InvalidStl,
+    SizeMismatch {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    Io(io::Error),
+}
+
+impl fmt::Display for RaytracerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RaytracerError::SingularMatrix => write!(f, "matrix is not invertible"),
+            RaytracerError::BadDimension { dimension, len } => write!(
+                f,
+                "cannot build a {dimension}x{dimension} matrix from {len} values"
+            ),
+            RaytracerError::UninitializedPattern => write!(f, "pattern is not set"),
+            RaytracerError::DegenerateNormal => write!(f, "normal is not a finite unit vector"),
+            RaytracerError::InvalidPpm => write!(f, "malformed ppm image"),
+            RaytracerError::InvalidStl => write!(f, "malformed stl file"),
+            RaytracerError::SizeMismatch { expected, found } => write!(
+                f,
+                "expected a {}x{} canvas, found {}x{}",
+                expected.0, expected.1, found.0, found.1
+            ),
+            RaytracerError::Io(err) => write!(f, "io error: {err}"),
+        }
+    }
+}
+
+impl Error for RaytracerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RaytracerError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RaytracerError {
+    fn from(err: io::Error) -> Self {
+        RaytracerError::Io(err)
+    }
+}
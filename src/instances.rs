@@ -0,0 +1,246 @@
+use crate::shape::next_ids;
+use crate::transformations::Transformable;
+use crate::{BoundingBox, Intersection, Material, Matrix, Object, Point, Ray, Shape, Vector};
+
+// One shared base object placed many times. Hits carry a copy of the base
+// with the instance transform baked in, so normals use the right placement.
+// Each placement owns a block of ids the size of the base's, so hits from
+// different placements are different objects.
+#[derive(Debug, Clone)]
+pub struct Instances {
+    pub transform: Matrix,
+    base: Box<Object>,
+    transforms: Vec<Matrix>,
+    inverses: Vec<Matrix>,
+    pub(crate) id: usize,
+}
+
+impl Instances {
+    #[must_use]
+    pub fn new(base: Object, transforms: Vec<Matrix>) -> Self {
+        let mut instances = Self {
+            transform: Matrix::default(),
+            base: Box::default(),
+            transforms,
+            inverses: Vec::new(),
+            id: 0,
+        };
+        instances.set_base(base);
+        instances
+    }
+
+    // Changes the id count, so a World re-numbers the object afterwards.
+    pub fn push(&mut self, transform: Matrix) {
+        self.inverses
+            .push((transform * self.base.get_transform()).inverse());
+        self.transforms.push(transform);
+        self.assign_ids(next_ids(self.id_count()));
+    }
+
+    #[must_use]
+    pub fn base(&self) -> &Object {
+        &self.base
+    }
+
+    pub fn set_base(&mut self, base: Object) {
+        let base_transform = base.get_transform();
+        self.inverses = self
+            .transforms
+            .iter()
+            .map(|transform| (*transform * base_transform).inverse())
+            .collect();
+        *self.base = base;
+        self.assign_ids(next_ids(self.id_count()));
+    }
+
+    #[must_use]
+    pub fn transforms(&self) -> &[Matrix] {
+        &self.transforms
+    }
+
+    pub(crate) fn id_count(&self) -> usize {
+        1 + self.transforms.len() * self.base.id_count()
+    }
+
+    pub(crate) fn assign_ids(&mut self, first: usize) {
+        self.id = first;
+        self.base.assign_ids(first + 1);
+    }
+
+    // Undoes the shift `local_intersect` gives hits from later placements.
+    pub(crate) fn includes_id(&self, id: usize) -> bool {
+        let size = self.base.id_count();
+        (0..self.transforms.len())
+            .map(|k| k * size)
+            .any(|shift| id >= shift && self.base.includes_id(id - shift))
+    }
+}
+
+impl PartialEq for Instances {
+    fn eq(&self, other: &Self) -> bool {
+        self.transform == other.transform
+            && self.base == other.base
+            && self.transforms == other.transforms
+    }
+}
+
+impl Default for Instances {
+    fn default() -> Self {
+        Self::new(Object::default(), Vec::new())
+    }
+}
+
+impl Transformable for Instances {
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Shape for Instances {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn get_material(&self) -> Material {
+        self.base.get_material()
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.base.set_material(material);
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection> {
+        let bounds = self.base.bounds();
+        let size = self.base.id_count();
+        let mut xs = Vec::new();
+        for (k, (transform, inverse)) in self.transforms.iter().zip(&self.inverses).enumerate() {
+            let local_ray = ray.transform(inverse);
+            if !bounds.intersects_ray(&local_ray) {
+                continue;
+            }
+
+            let placement = self.transform * *transform;
+            xs.extend(
+                self.base
+                    .local_intersect(&local_ray)
+                    .into_iter()
+                    .map(|mut i| {
+                        i.object.set_transform(placement * i.object.get_transform());
+                        let id = i.object.id();
+                        i.object.assign_ids(id + k * size);
+                        i
+                    }),
+            );
+        }
+        xs
+    }
+
+    fn local_normal_at(&self, point: Point) -> Vector {
+        self.base.local_normal_at(point)
+    }
+
+    fn local_normal_at_uv(&self, point: Point, u: f64, v: f64) -> Vector {
+        self.base.local_normal_at_uv(point, u, v)
+    }
+
+    fn local_face_normal_at(&self, point: Point) -> Vector {
+        self.base.local_face_normal_at(point)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let base = self.base.parent_space_bounds();
+        self.transforms
+            .iter()
+            .fold(BoundingBox::empty(), |acc, transform| {
+                acc.merge(&base.transform(*transform))
+            })
+    }
+
+    fn uv_at(&self, point: Point) -> Option<(f64, f64)> {
+        self.base.uv_at(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{vector, Sphere};
+
+    #[test]
+    fn instances_match_separate_spheres() {
+        let placements = vec![
+            Matrix::translation(Vector::new(0.0, 0.0, 3.0)),
+            Matrix::translation(Vector::new(0.0, 0.0, -4.0))
+                * Matrix::scaling(Vector::new(2.0, 2.0, 2.0)),
+        ];
+        let instances = Object::Instances(Instances::new(
+            Object::Sphere(Sphere::default()),
+            placements.clone(),
+        ));
+        let separate: Vec<Object> = placements
+            .iter()
+            .map(|m| Object::Sphere(Sphere::new(*m, Material::default())))
+            .collect();
+
+        let ray = Ray::new(Point::new(0.0, 0.5, -10.0), vector::Z);
+        let mut xs = ray.intersect(&instances);
+        let mut expected: Vec<Intersection> =
+            separate.iter().flat_map(|s| ray.intersect(s)).collect();
+        xs.sort_by(|a, b| a.t.total_cmp(&b.t));
+        expected.sort_by(|a, b| a.t.total_cmp(&b.t));
+
+        assert_eq!(xs.len(), 4);
+        assert_eq!(xs, expected);
+        for (hit, other) in xs.iter().zip(&expected) {
+            let point = ray.position(hit.t);
+            assert_eq!(hit.object.normal_at(point), other.object.normal_at(point));
+        }
+
+        assert_eq!(
+            instances.bounds(),
+            BoundingBox::new(Point::new(-2.0, -2.0, -6.0), Point::new(2.0, 2.0, 4.0))
+        );
+    }
+
+    #[test]
+    fn each_instance_is_its_own_object() {
+        let placements = vec![
+            Matrix::translation(Vector::new(0.0, 0.0, 3.0)),
+            Matrix::translation(Vector::new(0.0, 0.0, -3.0)),
+        ];
+        let object = Object::Instances(Instances::new(
+            Object::Sphere(Sphere::default()),
+            placements,
+        ));
+        let other = Object::Sphere(Sphere::default());
+
+        let xs = Ray::new(Point::new(0.0, 0.0, -10.0), vector::Z).intersect(&object);
+        assert_eq!(xs.len(), 4);
+        assert!(xs[0].object.same_object(&xs[1].object));
+        assert!(!xs[0].object.same_object(&xs[2].object));
+        assert!(xs.iter().all(|i| object.includes(&i.object)));
+        assert!(xs.iter().all(|i| !i.object.same_object(&other)));
+        assert!(!object.includes(&other));
+    }
+
+    #[test]
+    fn set_base_updates_placements() {
+        let mut instances = Instances::new(
+            Object::Sphere(Sphere::default()),
+            vec![Matrix::translation(Vector::new(0.0, 0.0, 3.0))],
+        );
+        instances.set_base(Object::Sphere(Sphere::new(
+            Matrix::scaling(Vector::new(2.0, 2.0, 2.0)),
+            Material::default(),
+        )));
+
+        let xs = Ray::new(Point::new(0.0, 0.0, -10.0), vector::Z).intersect(&instances);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 11.0);
+        assert_eq!(xs[1].t, 15.0);
+    }
+}
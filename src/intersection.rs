@@ -1,80 +1,177 @@
 use crate::utils::EPSILON;
-use crate::{Object, Point, Ray, Shape, Vector};
+use crate::{Color, Object, Point, Ray, Shape, Vector};
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Intersection {
     pub t: f64,
     pub object: Object,
+    pub uv: Option<(f64, f64)>,
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Computations {
     pub t: f64,
     pub object: Object,
     pub point: Point,
     pub eyev: Vector,
     pub normal: Vector,
+    pub reflectv: Vector,
+    pub geometric_normal: Vector,
     pub inside: bool,
     pub over_point: Point,
+    pub under_point: Point,
+    pub n1: f64,
+    pub n2: f64,
+    pub footprint: f64,
+    pub throughput: Color,
 }
 
 impl Intersection {
     #[must_use]
     pub fn new(t: f64, object: &Object) -> Self {
-        Self { t, object: *object }
+        Self {
+            t,
+            object: object.clone(),
+            uv: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_uv(t: f64, object: &Object, u: f64, v: f64) -> Self {
+        Self {
+            uv: Some((u, v)),
+            ..Self::new(t, object)
+        }
     }
 
     #[must_use]
     pub fn hit(intersections: &[Self]) -> Option<Self> {
+        Self::hit_after(intersections, 0.0)
+    }
+
+    #[must_use]
+    pub fn hit_after(intersections: &[Self], t_min: f64) -> Option<Self> {
         let i = intersections
             .iter()
-            .filter(|i| i.t > 0.0)
+            .filter(|i| i.t > t_min)
             .min_by(|i, j| i.t.partial_cmp(&j.t).unwrap())?;
 
-        Some(*i)
+        Some(i.clone())
     }
 
     #[must_use]
-    pub fn prepare_computations(&self, ray: &Ray) -> Computations {
+    pub fn prepare_computations(&self, ray: &Ray, intersections: &[Self]) -> Computations {
         let point = ray.position(self.t);
         let eyev = -ray.direction;
-        let normal = self.object.normal_at(point);
+        let mut normal = self.object.normal_at_uv(point, self.uv);
+        if let Some(bump) = self.object.get_material().bump {
+            normal = bump.perturb(&self.object, point, normal);
+        }
         let inside = normal.dot(&eyev) < 0.0;
         let normal = if inside { -normal } else { normal };
+        let geometric_normal = self.object.face_normal_at(point);
+        let geometric_normal = if geometric_normal.dot(&eyev) < 0.0 {
+            -geometric_normal
+        } else {
+            geometric_normal
+        };
+        let (n1, n2) = refraction_indices(self, intersections);
 
         Computations {
             t: self.t,
-            object: self.object,
+            object: self.object.clone(),
             point,
             eyev,
             normal,
+            reflectv: ray.direction.reflect(&normal),
+            geometric_normal,
             inside,
-            over_point: point + normal * EPSILON,
+            over_point: point + geometric_normal * EPSILON,
+            under_point: point - geometric_normal * EPSILON,
+            n1,
+            n2,
+            footprint: ray.footprint(self.t),
+            throughput: ray.throughput,
+        }
+    }
+}
+
+impl Computations {
+    // Schlick's approximation of the fraction of light reflected rather
+    // than refracted.
+    #[must_use]
+    pub fn schlick(&self) -> f64 {
+        let mut cos = self.eyev.dot(&self.normal);
+        if self.n1 > self.n2 {
+            let ratio = self.n1 / self.n2;
+            let sin2_t = ratio * ratio * (1.0 - cos * cos);
+            if sin2_t > 1.0 {
+                return 1.0;
+            }
+            cos = (1.0 - sin2_t).sqrt();
+        }
+
+        let r0 = ((self.n1 - self.n2) / (self.n1 + self.n2)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+    }
+}
+
+#[must_use]
+pub fn refraction_indices(hit: &Intersection, intersections: &[Intersection]) -> (f64, f64) {
+    let mut containers: Vec<&Object> = Vec::new();
+    let mut n1 = 1.0;
+
+    for i in intersections {
+        let is_hit = i == hit;
+        if is_hit {
+            n1 = containers
+                .last()
+                .map_or(1.0, |object| object.get_material().refractive_index);
+        }
+
+        match containers
+            .iter()
+            .position(|object| object.same_object(&i.object))
+        {
+            Some(index) => {
+                containers.remove(index);
+            }
+            None => containers.push(&i.object),
+        }
+
+        if is_hit {
+            let n2 = containers
+                .last()
+                .map_or(1.0, |object| object.get_material().refractive_index);
+            return (n1, n2);
         }
     }
+
+    (n1, 1.0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{vector, Material, Matrix, Sphere};
+    use crate::pattern::GradientPattern;
+    use crate::utils::equal;
+    use crate::{vector, Bump, Material, Matrix, Pattern, Plane, SmoothTriangle, Sphere};
+
+    use std::slice;
 
     #[test]
     fn new_intersection() {
-        let s = Sphere::default();
-        let i = Intersection::new(3.5, &Object::Sphere(s));
+        let s = Object::Sphere(Sphere::default());
+        let i = Intersection::new(3.5, &s);
 
         assert_eq!(i.t, 3.5);
-        assert_eq!(i.object, Object::Sphere(s));
+        assert_eq!(i.object, s);
     }
 
     #[test]
     fn hit_positive() {
-        let s = Sphere::default();
-        let intersections = vec![
-            Intersection::new(1.0, &Object::Sphere(s)),
-            Intersection::new(2.0, &Object::Sphere(s)),
-        ];
+        let s = Object::Sphere(Sphere::default());
+        let intersections = vec![Intersection::new(1.0, &s), Intersection::new(2.0, &s)];
         let i = Intersection::hit(&intersections).unwrap();
 
         assert_eq!(i.t, 1.0);
@@ -82,11 +179,8 @@ mod tests {
 
     #[test]
     fn hit_negative() {
-        let s = Sphere::default();
-        let intersections = vec![
-            Intersection::new(1.0, &Object::Sphere(s)),
-            Intersection::new(-1.0, &Object::Sphere(s)),
-        ];
+        let s = Object::Sphere(Sphere::default());
+        let intersections = vec![Intersection::new(1.0, &s), Intersection::new(-1.0, &s)];
         let i = Intersection::hit(&intersections).unwrap();
 
         assert_eq!(i.t, 1.0);
@@ -94,11 +188,8 @@ mod tests {
 
     #[test]
     fn hit_all_negative() {
-        let s = Sphere::default();
-        let intersections = vec![
-            Intersection::new(-2.0, &Object::Sphere(s)),
-            Intersection::new(-1.0, &Object::Sphere(s)),
-        ];
+        let s = Object::Sphere(Sphere::default());
+        let intersections = vec![Intersection::new(-2.0, &s), Intersection::new(-1.0, &s)];
         let i = Intersection::hit(&intersections);
 
         assert!(i.is_none());
@@ -106,24 +197,37 @@ mod tests {
 
     #[test]
     fn hit_big() {
-        let s = Sphere::default();
+        let s = Object::Sphere(Sphere::default());
         let intersections = vec![
-            Intersection::new(5.0, &Object::Sphere(s)),
-            Intersection::new(7.0, &Object::Sphere(s)),
-            Intersection::new(-3.0, &Object::Sphere(s)),
-            Intersection::new(2.0, &Object::Sphere(s)),
+            Intersection::new(5.0, &s),
+            Intersection::new(7.0, &s),
+            Intersection::new(-3.0, &s),
+            Intersection::new(2.0, &s),
         ];
         let i = Intersection::hit(&intersections).unwrap();
 
         assert_eq!(i.t, 2.0);
     }
 
+    #[test]
+    fn hit_after_min_t() {
+        let s = Object::Sphere(Sphere::default());
+        let intersections = vec![Intersection::new(1e-6, &s), Intersection::new(3.0, &s)];
+
+        assert_eq!(Intersection::hit(&intersections).unwrap().t, 1e-6);
+        assert_eq!(
+            Intersection::hit_after(&intersections, EPSILON).unwrap().t,
+            3.0
+        );
+        assert!(Intersection::hit_after(&intersections, 5.0).is_none());
+    }
+
     #[test]
     fn precomputations() {
         let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
         let s = Sphere::default();
-        let i = ray.intersect(&s)[0];
-        let comps = i.prepare_computations(&ray);
+        let i = ray.intersect(&s)[0].clone();
+        let comps = i.prepare_computations(&ray, slice::from_ref(&i));
 
         assert_eq!(comps.t, i.t);
         assert_eq!(comps.object, i.object);
@@ -137,7 +241,7 @@ mod tests {
         let ray = Ray::new(Point::default(), Vector::new(0.0, 0.0, 1.0));
         let s = Sphere::default();
         let i = Intersection::new(1.0, &Object::Sphere(s));
-        let comps = i.prepare_computations(&ray);
+        let comps = i.prepare_computations(&ray, slice::from_ref(&i));
 
         assert_eq!(comps.point, Point::new(0.0, 0.0, 1.0));
         assert_eq!(comps.eyev, Vector::new(0.0, 0.0, -1.0));
@@ -150,9 +254,145 @@ mod tests {
         let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
         let s = Sphere::new(Matrix::translation(vector::Z), Material::default());
         let i = Intersection::new(5.0, &Object::Sphere(s));
-        let comps = i.prepare_computations(&ray);
+        let comps = i.prepare_computations(&ray, slice::from_ref(&i));
 
         assert!(comps.over_point.z < -EPSILON / 2.0);
         assert!(comps.over_point.z < comps.point.z);
+        assert_eq!(comps.geometric_normal, comps.normal);
+    }
+
+    #[test]
+    fn smooth_triangle_over_point_follows_face_normal() {
+        let tilted = Vector::new(1.0, 0.0, -1.0).normalize();
+        let triangle = Object::SmoothTriangle(SmoothTriangle::new(
+            Point::new(0.0, 1.0, 0.0),
+            Point::new(-1.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            tilted,
+            tilted,
+            tilted,
+        ));
+        let ray = Ray::new(Point::new(0.0, 0.5, -5.0), vector::Z);
+        let i = ray.intersect(&triangle)[0].clone();
+        let comps = i.prepare_computations(&ray, slice::from_ref(&i));
+
+        assert_eq!(comps.normal, tilted);
+        assert_eq!(comps.geometric_normal, -vector::Z);
+        // Scaled up, since points only compare equal to within EPSILON.
+        assert_eq!((comps.over_point - comps.point) / EPSILON, -vector::Z);
+        assert_eq!((comps.under_point - comps.point) / EPSILON, vector::Z);
+    }
+
+    #[test]
+    fn bump_tilts_shading_normal() {
+        let mut plane = Plane::default();
+        let mut material = plane.get_material();
+        material.bump = Some(Bump::new(
+            Pattern::Gradient(GradientPattern::default()),
+            0.5,
+        ));
+        plane.set_material(material);
+        let shape = Object::Plane(plane);
+
+        let ray = Ray::new(Point::new(0.5, 1.0, 0.0), -vector::Y);
+        let i = Intersection::new(1.0, &shape);
+        let comps = i.prepare_computations(&ray, slice::from_ref(&i));
+
+        // Height falls off along +x, so the normal leans that way.
+        assert_eq!(comps.normal, Vector::new(0.5, 1.0, 0.0).normalize());
+        assert_eq!(comps.geometric_normal, vector::Y);
+    }
+
+    #[test]
+    fn precomputations_reflectv() {
+        let shape = Object::Plane(Plane::default());
+        let half = 2_f64.sqrt() / 2.0;
+        let ray = Ray::new(Point::new(0.0, 1.0, -1.0), Vector::new(0.0, -half, half));
+        let i = Intersection::new(2_f64.sqrt(), &shape);
+        let comps = i.prepare_computations(&ray, slice::from_ref(&i));
+
+        assert_eq!(comps.reflectv, Vector::new(0.0, half, half));
+    }
+
+    #[test]
+    fn precomputations_under_point() {
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
+        let shape = glass_sphere(Matrix::translation(vector::Z), 1.5);
+        let i = Intersection::new(5.0, &shape);
+        let comps = i.prepare_computations(&ray, slice::from_ref(&i));
+
+        assert!(comps.under_point.z > EPSILON / 2.0);
+        assert!(comps.point.z < comps.under_point.z);
+    }
+
+    fn glass_sphere(transform: Matrix, refractive_index: f64) -> Object {
+        let mut sphere = Sphere::glass();
+        sphere.transform = transform;
+        sphere.material.refractive_index = refractive_index;
+        Object::Sphere(sphere)
+    }
+
+    #[test]
+    fn schlick_approximation() {
+        let shape = glass_sphere(Matrix::default(), 1.5);
+        let half = 2_f64.sqrt() / 2.0;
+        let cases = [
+            // Total internal reflection.
+            (
+                Ray::new(Point::new(0.0, 0.0, half), vector::Y),
+                vec![-half, half],
+                1,
+                1.0,
+            ),
+            // Perpendicular viewing angle.
+            (
+                Ray::new(Point::default(), vector::Y),
+                vec![-1.0, 1.0],
+                1,
+                0.04,
+            ),
+            // Small angle with n2 > n1.
+            (
+                Ray::new(Point::new(0.0, 0.99, -2.0), vector::Z),
+                vec![1.8589],
+                0,
+                0.48873,
+            ),
+        ];
+
+        for (ray, ts, hit, reflectance) in cases {
+            let xs: Vec<_> = ts.iter().map(|&t| Intersection::new(t, &shape)).collect();
+            let comps = xs[hit].prepare_computations(&ray, &xs);
+            assert!(equal(comps.schlick(), reflectance));
+        }
+    }
+
+    #[test]
+    fn refraction_indices_table() {
+        let a = glass_sphere(Matrix::scaling(Vector::new(2.0, 2.0, 2.0)), 1.5);
+        let b = glass_sphere(Matrix::translation(Vector::new(0.0, 0.0, -0.25)), 2.0);
+        let c = glass_sphere(Matrix::translation(Vector::new(0.0, 0.0, 0.25)), 2.5);
+        let xs = vec![
+            Intersection::new(2.0, &a),
+            Intersection::new(2.75, &b),
+            Intersection::new(3.25, &c),
+            Intersection::new(4.75, &b),
+            Intersection::new(5.25, &c),
+            Intersection::new(6.0, &a),
+        ];
+        let expected = [
+            (1.0, 1.5),
+            (1.5, 2.0),
+            (2.0, 2.5),
+            (2.5, 2.5),
+            (2.5, 1.5),
+            (1.5, 1.0),
+        ];
+
+        for (hit, (n1, n2)) in xs.iter().zip(expected) {
+            let (r1, r2) = refraction_indices(hit, &xs);
+            assert!(equal(r1, n1));
+            assert!(equal(r2, n2));
+        }
     }
 }
@@ -1,36 +1,73 @@
 #![warn(clippy::all, clippy::pedantic)]
-#![allow(clippy::missing_panics_doc)]
+#![allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
 
+pub mod animation;
+pub mod bounds;
+pub mod bvh;
 pub mod camera;
 pub mod canvas;
+pub mod clipped_shape;
 pub mod color;
+pub mod cone;
+pub mod csg;
+pub mod cube;
+pub mod cylinder;
+pub mod disc;
+pub mod error;
+pub mod instances;
 pub mod intersection;
 pub mod light;
 pub mod material;
 pub mod matrix;
+pub mod mesh;
+pub mod parse;
 pub mod pattern;
 pub mod plane;
 pub mod point;
 pub mod ray;
+pub mod rng;
+pub mod scene_hash;
+pub mod sdf;
 pub mod shape;
+pub mod smooth_triangle;
 pub mod sphere;
+pub mod stl;
 pub mod transformations;
+mod tuple;
 pub mod utils;
+pub mod uv;
 pub mod vector;
 pub mod world;
 
-pub use camera::Camera;
-pub use canvas::Canvas;
+pub use animation::Animation;
+pub use bounds::BoundingBox;
+pub use bvh::Bvh;
+pub use camera::{Camera, Sampler};
+pub use canvas::{BlendMode, Canvas, ClampMode};
+pub use clipped_shape::ClippedShape;
 pub use color::Color;
-pub use intersection::{Computations, Intersection};
-pub use light::PointLight;
-pub use material::Material;
+pub use cone::Cone;
+pub use csg::{Csg, CsgOperation};
+pub use cube::Cube;
+pub use cylinder::Cylinder;
+pub use disc::Disc;
+pub use error::RaytracerError;
+pub use instances::Instances;
+pub use intersection::{refraction_indices, Computations, Intersection};
+pub use light::{AreaLight, Light, LightSampling, PointLight};
+pub use material::{Bump, Coat, LightingComponents, Material, Pbr};
 pub use matrix::Matrix;
-pub use pattern::{Pattern, Patterned};
+pub use mesh::Mesh;
+pub use parse::ParseError;
+pub use pattern::{CustomPattern, Pattern, Patterned};
 pub use plane::Plane;
 pub use point::Point;
 pub use ray::Ray;
+pub use sdf::SdfShape;
 pub use shape::{Object, Shape};
+pub use smooth_triangle::SmoothTriangle;
 pub use sphere::Sphere;
+pub use stl::read_stl;
+pub use uv::UvMapping;
 pub use vector::Vector;
-pub use world::World;
+pub use world::{Background, Fog, HitInfo, World};
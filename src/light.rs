@@ -1,4 +1,7 @@
-use crate::{Color, Point};
+use crate::rng::Rng;
+use crate::{Color, Point, Vector, World};
+
+const AREA_LIGHT_SEED: u64 = 0x1167;
 
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Default, Copy, Clone, PartialEq)]
@@ -17,9 +20,252 @@ impl PointLight {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Light {
+    Point(PointLight),
+    Area(AreaLight),
+}
+
+impl Light {
+    #[must_use]
+    pub fn position(&self) -> Point {
+        match self {
+            Light::Point(light) => light.position,
+            Light::Area(light) => light.position(),
+        }
+    }
+
+    #[must_use]
+    pub fn intensity(&self) -> Color {
+        match self {
+            Light::Point(light) => light.intensity,
+            Light::Area(light) => light.intensity,
+        }
+    }
+
+    #[must_use]
+    pub fn samples(&self) -> usize {
+        match self {
+            Light::Point(_) => 1,
+            Light::Area(light) => light.samples(),
+        }
+    }
+
+    #[must_use]
+    pub fn as_point(&self) -> PointLight {
+        PointLight::new(self.position(), self.intensity())
+    }
+
+    #[must_use]
+    pub fn intensity_at(&self, point: Point, world: &World) -> f64 {
+        self.samples_at(point, world)
+            .iter()
+            .map(|(_, share)| share)
+            .sum()
+    }
+
+    // Positions that light `point`, each with the share of the light that
+    // reaches it through transparent occluders.
+    #[must_use]
+    pub fn samples_at(&self, point: Point, world: &World) -> Vec<(PointLight, f64)> {
+        match self {
+            Light::Point(light) => {
+                vec![(*light, world.shadow_attenuation(point, light).luminance())]
+            }
+            Light::Area(light) => light.samples_at_with(
+                point,
+                world,
+                LightSampling::Stratified,
+                &mut Rng::new(light.seed),
+            ),
+        }
+    }
+}
+
+impl From<PointLight> for Light {
+    fn from(light: PointLight) -> Self {
+        Light::Point(light)
+    }
+}
+
+impl From<AreaLight> for Light {
+    fn from(light: AreaLight) -> Self {
+        Light::Area(light)
+    }
+}
+
+// Where shadow rays land on an area light: anywhere at random, or jittered
+// within each cell of the usteps × vsteps grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightSampling {
+    Uniform,
+    Stratified,
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AreaLight {
+    pub corner: Point,
+    pub uvec: Vector,
+    pub usteps: usize,
+    pub vvec: Vector,
+    pub vsteps: usize,
+    pub intensity: Color,
+    pub seed: u64,
+    // Scale each unoccluded sample by the luminance of the background seen in
+    // its direction, so a dim sky yields a dimmer soft light.
+    pub sky_weighted: bool,
+}
+
+impl AreaLight {
+    #[allow(clippy::similar_names)]
+    #[must_use]
+    pub fn new(
+        corner: Point,
+        uvec: Vector,
+        usteps: usize,
+        vvec: Vector,
+        vsteps: usize,
+        intensity: Color,
+    ) -> Self {
+        Self {
+            corner,
+            uvec,
+            usteps,
+            vvec,
+            vsteps,
+            intensity,
+            seed: AREA_LIGHT_SEED,
+            sky_weighted: false,
+        }
+    }
+
+    #[must_use]
+    pub fn samples(&self) -> usize {
+        self.usteps * self.vsteps
+    }
+
+    #[must_use]
+    pub fn position(&self) -> Point {
+        self.corner + self.uvec * 0.5 + self.vvec * 0.5
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn point_on_light(&self, u: f64, v: f64) -> Point {
+        self.corner + self.uvec * (u / self.usteps as f64) + self.vvec * (v / self.vsteps as f64)
+    }
+
+    #[must_use]
+    pub fn intensity_at(&self, point: Point, world: &World) -> f64 {
+        self.intensity_at_with(
+            point,
+            world,
+            LightSampling::Stratified,
+            &mut Rng::new(self.seed),
+        )
+    }
+
+    // Visible fraction of the light seen from `point`, weighting samples by cos/d².
+    #[must_use]
+    pub fn intensity_at_with(
+        &self,
+        point: Point,
+        world: &World,
+        sampling: LightSampling,
+        rng: &mut Rng,
+    ) -> f64 {
+        self.samples_at_with(point, world, sampling, rng)
+            .iter()
+            .map(|(_, share)| share)
+            .sum()
+    }
+
+    // One sample per cell, each weighted by cos/d² and divided by the total
+    // weight. Stratifying spreads the samples out; it isn't importance sampling.
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn samples_at_with(
+        &self,
+        point: Point,
+        world: &World,
+        sampling: LightSampling,
+        rng: &mut Rng,
+    ) -> Vec<(PointLight, f64)> {
+        let normal = self.uvec.cross(&self.vvec).normalize();
+        let mut samples = Vec::with_capacity(self.samples());
+        let mut total = 0.0;
+
+        for v in 0..self.vsteps {
+            for u in 0..self.usteps {
+                let sample = match sampling {
+                    _ if self.samples() == 1 => self.position(),
+                    LightSampling::Uniform => self.point_on_light(
+                        rng.next_f64() * self.usteps as f64,
+                        rng.next_f64() * self.vsteps as f64,
+                    ),
+                    LightSampling::Stratified => {
+                        self.point_on_light(u as f64 + rng.next_f64(), v as f64 + rng.next_f64())
+                    }
+                };
+
+                let direction = sample - point;
+                let distance = direction.magnitude();
+                let direction = direction.normalize();
+                let weight = direction.dot(&normal).abs() / (distance * distance);
+
+                total += weight;
+                let light = PointLight::new(sample, self.intensity);
+                let mut share = weight * world.shadow_attenuation(point, &light).luminance();
+                if self.sky_weighted {
+                    share *= world.background.color_at(direction).luminance();
+                }
+                samples.push((light, share));
+            }
+        }
+
+        for (_, share) in &mut samples {
+            *share = if total > 0.0 { *share / total } else { 0.0 };
+        }
+        samples
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_light {
+    use super::*;
+    use crate::{Cube, Material, Matrix, Object, Shape};
+
+    // 2×2 light at y = 10, centered over the origin.
+    pub fn area_light(steps: usize) -> AreaLight {
+        AreaLight::new(
+            Point::new(-1.0, 10.0, -1.0),
+            Vector::new(2.0, 0.0, 0.0),
+            steps,
+            Vector::new(0.0, 0.0, 2.0),
+            steps,
+            Color::white(),
+        )
+    }
+
+    // Thin 10×10 slab at y = 5 centered on `x`. At x = 5 it hides the half of
+    // the light above x > 0 from the origin.
+    pub fn occluder(x: f64, material: Material) -> Object {
+        Object::Cube(Cube::new(
+            Matrix::translation(Vector::new(x, 5.0, 0.0))
+                * Matrix::scaling(Vector::new(5.0, 0.01, 5.0)),
+            material,
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::test_light::{area_light, occluder};
     use super::*;
+    use crate::utils::equal;
+    use crate::world::Background;
+    use crate::Material;
 
     #[test]
     fn new_point_light() {
@@ -28,4 +274,86 @@ mod tests {
         assert_eq!(l.position, Point::new(0.0, 0.0, 0.0));
         assert_eq!(l.intensity, Color::new(1.0, 1.0, 1.0));
     }
+
+    #[test]
+    fn sky_weighted_area_light() {
+        let mut world = World::new(
+            vec![occluder(5.0, Material::default())],
+            PointLight::default(),
+        );
+        let mut light = area_light(4);
+        let open = Point::new(-20.0, 0.0, 0.0);
+
+        let fraction = light.intensity_at(Point::default(), &world);
+        assert!(fraction > 0.0 && fraction < 1.0);
+        assert!(equal(light.intensity_at(open, &world), 1.0));
+
+        world.background = Background::Solid(Color::new(0.5, 0.5, 0.5));
+        assert!(equal(
+            light.intensity_at(Point::default(), &world),
+            fraction
+        ));
+        assert!(equal(light.intensity_at(open, &world), 1.0));
+
+        light.sky_weighted = true;
+        assert!(equal(
+            light.intensity_at(Point::default(), &world),
+            fraction * 0.5
+        ));
+        assert!(equal(light.intensity_at(open, &world), 0.5));
+    }
+
+    #[test]
+    fn area_light_through_transparent_occluder() {
+        let glass = Material {
+            transparency: 0.5,
+            ..Material::default()
+        };
+        let world = World::new(vec![occluder(0.0, glass)], PointLight::default());
+        let light = Light::Area(area_light(2));
+
+        assert!(equal(light.intensity_at(Point::default(), &world), 0.5));
+        // Each sample carries about a quarter of the light, halved by the slab.
+        let samples = light.samples_at(Point::default(), &world);
+        assert_eq!(samples.len(), 4);
+        for (_, share) in samples {
+            assert!((share - 0.125).abs() < 0.01, "share {}", share);
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    #[test]
+    fn stratified_sampling_reduces_variance() {
+        let world = World::new(
+            vec![occluder(5.0, Material::default())],
+            PointLight::default(),
+        );
+        let light = area_light(4);
+
+        let stats = |sampling| {
+            let estimates: Vec<f64> = (0..200)
+                .map(|seed| {
+                    light.intensity_at_with(Point::default(), &world, sampling, &mut Rng::new(seed))
+                })
+                .collect();
+            let mean = estimates.iter().sum::<f64>() / estimates.len() as f64;
+            let variance =
+                estimates.iter().map(|e| (e - mean).powi(2)).sum::<f64>() / estimates.len() as f64;
+            (mean, variance)
+        };
+        let (uniform_mean, uniform_variance) = stats(LightSampling::Uniform);
+        let (mean, variance) = stats(LightSampling::Stratified);
+
+        assert!(mean > 0.3 && mean < 0.7);
+        assert!((mean - uniform_mean).abs() < 0.02);
+        assert!(variance < uniform_variance / 2.0);
+        assert!(equal(
+            light.intensity_at(Point::default(), &world),
+            light.intensity_at(Point::default(), &world)
+        ));
+        assert!(equal(
+            light.intensity_at(Point::new(-20.0, 0.0, 0.0), &world),
+            1.0
+        ));
+    }
 }
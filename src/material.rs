@@ -1,7 +1,12 @@
+use crate::parse::{parse_toml, ParseError};
+use crate::pattern::{CheckerPattern, GradientPattern, RingPattern, StripePattern};
+use crate::transformations::Transformable;
 use crate::utils::equal;
-use crate::{Color, Object, Pattern, Patterned, Point, PointLight, Vector};
+use crate::{Color, Matrix, Object, Pattern, Patterned, Point, PointLight, Vector};
 
-#[derive(Debug, Clone, Copy)]
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone)]
 pub struct Material {
     pub color: Color,
     pub pattern: Pattern,
@@ -9,6 +14,19 @@ pub struct Material {
     pub diffuse: f64,
     pub specular: f64,
     pub shininess: f64,
+    pub transparency: f64,
+    pub refractive_index: f64,
+    pub reflective: f64,
+    pub roughness: f64,
+    pub normal_tint: Option<(Color, Color)>,
+    pub smooth_normals: bool,
+    pub material_transform: Matrix,
+    pub specular_color: Option<Color>,
+    pub emissive: Color,
+    pub coat: Option<Coat>,
+    // Replaces the Phong diffuse and specular terms when set.
+    pub pbr: Option<Pbr>,
+    pub bump: Option<Bump>,
 }
 
 impl PartialEq for Material {
@@ -18,6 +36,18 @@ impl PartialEq for Material {
             && equal(self.diffuse, other.diffuse)
             && equal(self.specular, other.specular)
             && equal(self.shininess, other.shininess)
+            && equal(self.transparency, other.transparency)
+            && equal(self.refractive_index, other.refractive_index)
+            && equal(self.reflective, other.reflective)
+            && equal(self.roughness, other.roughness)
+            && self.normal_tint == other.normal_tint
+            && self.smooth_normals == other.smooth_normals
+            && self.material_transform == other.material_transform
+            && self.specular_color == other.specular_color
+            && self.emissive == other.emissive
+            && self.coat == other.coat
+            && self.pbr == other.pbr
+            && self.bump == other.bump
     }
 }
 
@@ -30,6 +60,18 @@ impl Default for Material {
             diffuse: 0.9,
             specular: 0.9,
             shininess: 200.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
+            reflective: 0.0,
+            roughness: 0.0,
+            normal_tint: None,
+            smooth_normals: true,
+            material_transform: Matrix::default(),
+            specular_color: None,
+            emissive: Color::black(),
+            coat: None,
+            pbr: None,
+            bump: None,
         }
     }
 }
@@ -51,6 +93,165 @@ impl Material {
             diffuse,
             specular,
             shininess,
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn with_pattern(pattern: Pattern, transform: Matrix) -> Self {
+        let mut material = Self {
+            pattern,
+            ..Self::default()
+        };
+        material.set_pattern_transform(transform);
+        material
+    }
+
+    pub fn set_pattern_transform(&mut self, transform: Matrix) {
+        self.pattern.set_transform(transform);
+    }
+
+    #[must_use]
+    pub fn matte(color: Color) -> Self {
+        Self {
+            color,
+            diffuse: 0.9,
+            specular: 0.0,
+            shininess: 10.0,
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn plastic(color: Color) -> Self {
+        Self {
+            color,
+            diffuse: 0.8,
+            specular: 0.5,
+            shininess: 150.0,
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn metal(color: Color) -> Self {
+        Self {
+            color,
+            ambient: 0.05,
+            diffuse: 0.3,
+            specular: 1.0,
+            shininess: 300.0,
+            reflective: 0.4,
+            specular_color: Some(color),
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn mirror() -> Self {
+        Self {
+            color: Color::black(),
+            ambient: 0.0,
+            diffuse: 0.05,
+            specular: 1.0,
+            shininess: 400.0,
+            reflective: 1.0,
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn pbr(color: Color, metallic: f64, roughness: f64) -> Self {
+        Self {
+            color,
+            pbr: Some(Pbr {
+                metallic,
+                roughness,
+            }),
+            ..Self::default()
+        }
+    }
+
+    // Reflection and refraction are split by the Fresnel term in shade_hit.
+    #[must_use]
+    pub fn glass() -> Self {
+        Self {
+            color: Color::black(),
+            ambient: 0.0,
+            diffuse: 0.1,
+            specular: 1.0,
+            shininess: 300.0,
+            reflective: 0.9,
+            transparency: 0.9,
+            refractive_index: 1.5,
+            ..Self::default()
+        }
+    }
+
+    pub fn from_toml(s: &str) -> Result<Self, ParseError> {
+        let mut material = Self::default();
+
+        for (key, value) in parse_toml(s)? {
+            match key.as_str() {
+                "color" => {
+                    let (r, g, b) = value.triple(&key)?;
+                    material.color = Color::new(r, g, b);
+                }
+                "emissive" => {
+                    let (r, g, b) = value.triple(&key)?;
+                    material.emissive = Color::new(r, g, b);
+                }
+                "ambient" => material.ambient = value.number(&key)?,
+                "diffuse" => material.diffuse = value.number(&key)?,
+                "specular" => material.specular = value.number(&key)?,
+                "shininess" => material.shininess = value.number(&key)?,
+                "transparency" => material.transparency = value.number(&key)?,
+                "refractive_index" => material.refractive_index = value.number(&key)?,
+                "reflective" => material.reflective = value.number(&key)?,
+                "roughness" => material.roughness = value.number(&key)?,
+                "pattern" => {
+                    material.pattern = match value.string(&key)? {
+                        "none" => Pattern::None,
+                        "stripe" => Pattern::Stripe(StripePattern::default()),
+                        "gradient" => Pattern::Gradient(GradientPattern::default()),
+                        "ring" => Pattern::Ring(RingPattern::default()),
+                        "checker" => Pattern::Checker(CheckerPattern::default()),
+                        other => {
+                            return Err(ParseError::UnknownValue {
+                                key,
+                                value: other.to_string(),
+                            })
+                        }
+                    }
+                }
+                _ => return Err(ParseError::UnknownKey(key)),
+            }
+        }
+
+        Ok(material)
+    }
+
+    #[must_use]
+    pub fn filtered(&self, object: &Object, point: Point, normal: Vector, radius: f64) -> Self {
+        if radius <= 0.0 || self.pattern == Pattern::None {
+            return self.clone();
+        }
+
+        Self {
+            color: self
+                .pattern
+                .color_at_object_filtered(object, point, normal, radius),
+            pattern: Pattern::None,
+            ..self.clone()
+        }
+    }
+
+    #[must_use]
+    pub fn surface_color(&self, object: &Object, point: Point) -> Color {
+        if self.pattern == Pattern::None {
+            self.color
+        } else {
+            self.pattern.color_at_object_uv(object, point)
         }
     }
 
@@ -64,46 +265,258 @@ impl Material {
         normal: Vector,
         in_shadow: bool,
     ) -> Color {
-        let color = if self.pattern == Pattern::None {
-            self.color
+        let attenuation = if in_shadow {
+            Color::black()
+        } else {
+            Color::white()
+        };
+        self.lighting_attenuated(object, point, light, eyev, normal, attenuation)
+    }
+
+    #[must_use]
+    pub fn lighting_attenuated(
+        &self,
+        object: &Object,
+        point: Point,
+        light: PointLight,
+        eyev: Vector,
+        normal: Vector,
+        attenuation: Color,
+    ) -> Color {
+        self.lighting_components(object, point, light, eyev, normal, attenuation)
+            .total()
+    }
+
+    #[must_use]
+    pub fn lighting_components(
+        &self,
+        object: &Object,
+        point: Point,
+        light: PointLight,
+        eyev: Vector,
+        normal: Vector,
+        attenuation: Color,
+    ) -> LightingComponents {
+        let color = if let Some((facing, grazing)) = self.normal_tint {
+            grazing.lerp(facing, normal.dot(&eyev).clamp(0.0, 1.0))
         } else {
-            self.pattern.color_at_object(object, point)
+            self.surface_color(object, point)
         };
         let effective_color = color * light.intensity;
         let lightv = (light.position - point).normalize();
         let ambient = effective_color * self.ambient;
         let light_dot_normal = lightv.dot(&normal);
 
-        if in_shadow {
-            ambient
-        } else {
-            let diffuse;
-            let specular;
-
-            if light_dot_normal < 0.0 {
-                diffuse = Color::black();
-                specular = Color::black();
-            } else {
-                diffuse = effective_color * self.diffuse * light_dot_normal;
-                let reflectv = (-lightv).reflect(&normal);
-                let reflect_dot_eye = reflectv.dot(&eyev);
-                if reflect_dot_eye <= 0.0 {
-                    specular = Color::black();
-                } else {
-                    let factor = reflect_dot_eye.powf(self.shininess);
-                    specular = light.intensity * self.specular * factor;
-                }
-            }
+        let mut components = LightingComponents {
+            ambient,
+            diffuse: Color::black(),
+            specular: Color::black(),
+        };
+
+        if attenuation == Color::black() || light_dot_normal < 0.0 {
+            return components;
+        }
+
+        if let Some(pbr) = self.pbr {
+            let (diffuse, specular) = pbr.reflectance(color, lightv, eyev, normal);
+            let incoming = light.intensity * light_dot_normal * attenuation;
+            components.diffuse = diffuse * incoming;
+            components.specular = specular * incoming;
+            return components;
+        }
+
+        components.diffuse = effective_color * self.diffuse * light_dot_normal * attenuation;
+        let reflectv = (-lightv).reflect(&normal);
+        let reflect_dot_eye = reflectv.dot(&eyev);
+        if reflect_dot_eye > 0.0 {
+            let factor = reflect_dot_eye.powf(self.shininess);
+            let specular_color = self.specular_color.unwrap_or(light.intensity);
+            components.specular = specular_color * self.specular * factor * attenuation;
+        }
+
+        components
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Coat {
+    pub reflective: f64,
+    pub refractive_index: f64,
+    pub shininess: f64,
+}
+
+impl PartialEq for Coat {
+    fn eq(&self, other: &Self) -> bool {
+        equal(self.reflective, other.reflective)
+            && equal(self.refractive_index, other.refractive_index)
+            && equal(self.shininess, other.shininess)
+    }
+}
+
+impl Default for Coat {
+    fn default() -> Self {
+        Self {
+            reflective: 1.0,
+            refractive_index: 1.5,
+            shininess: 300.0,
+        }
+    }
+}
+
+impl Coat {
+    #[must_use]
+    pub fn weight(&self, eyev: Vector, normal: Vector) -> f64 {
+        let r0 = ((1.0 - self.refractive_index) / (1.0 + self.refractive_index)).powi(2);
+        let cos = eyev.dot(&normal).clamp(0.0, 1.0);
+        self.reflective * (r0 + (1.0 - r0) * (1.0 - cos).powi(5))
+    }
+
+    #[must_use]
+    pub fn specular(
+        &self,
+        point: Point,
+        light: PointLight,
+        eyev: Vector,
+        normal: Vector,
+        attenuation: Color,
+    ) -> Color {
+        let lightv = (light.position - point).normalize();
+        if lightv.dot(&normal) < 0.0 {
+            return Color::black();
+        }
+
+        let reflect_dot_eye = (-lightv).reflect(&normal).dot(&eyev);
+        if reflect_dot_eye <= 0.0 {
+            return Color::black();
+        }
+        light.intensity * reflect_dot_eye.powf(self.shininess) * attenuation
+    }
+}
+
+// Height field read from a pattern's luminance; only the shading normal is
+// tilted, so smooth patterns (gradients, rings) give the best results.
+#[derive(Debug, Clone)]
+pub struct Bump {
+    pub pattern: Pattern,
+    pub strength: f64,
+}
+
+impl PartialEq for Bump {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern && equal(self.strength, other.strength)
+    }
+}
+
+const BUMP_DELTA: f64 = 1e-3;
+
+impl Bump {
+    #[must_use]
+    pub fn new(pattern: Pattern, strength: f64) -> Self {
+        Self { pattern, strength }
+    }
 
-            ambient + diffuse + specular
+    // Tilts `normal` against the height gradient along the surface.
+    #[must_use]
+    pub fn perturb(&self, object: &Object, point: Point, normal: Vector) -> Vector {
+        if self.pattern == Pattern::None {
+            return normal;
         }
+
+        let height = |offset: Vector| {
+            self.pattern
+                .color_at_object(object, point + offset)
+                .luminance()
+        };
+        let slope = |axis: Vector| {
+            (height(axis * BUMP_DELTA) - height(axis * -BUMP_DELTA)) / (2.0 * BUMP_DELTA)
+        };
+        let gradient = Vector::new(
+            slope(Vector::new(1.0, 0.0, 0.0)),
+            slope(Vector::new(0.0, 1.0, 0.0)),
+            slope(Vector::new(0.0, 0.0, 1.0)),
+        );
+        let along_surface = gradient - normal * gradient.dot(&normal);
+        (normal - along_surface * self.strength).normalize()
+    }
+}
+
+// Metallic-roughness model: GGX distribution, Smith-Schlick geometry and
+// Schlick Fresnel, as used by glTF and most modern asset pipelines.
+#[derive(Debug, Clone, Copy)]
+pub struct Pbr {
+    pub metallic: f64,
+    pub roughness: f64,
+}
+
+impl PartialEq for Pbr {
+    fn eq(&self, other: &Self) -> bool {
+        equal(self.metallic, other.metallic) && equal(self.roughness, other.roughness)
+    }
+}
+
+impl Default for Pbr {
+    fn default() -> Self {
+        Self {
+            metallic: 0.0,
+            roughness: 0.5,
+        }
+    }
+}
+
+impl Pbr {
+    // Diffuse and specular reflectance toward the eye. Both are scaled by
+    // PI so a white light of intensity 1 matches the Phong brightness scale.
+    #[must_use]
+    pub fn reflectance(
+        &self,
+        base: Color,
+        lightv: Vector,
+        eyev: Vector,
+        normal: Vector,
+    ) -> (Color, Color) {
+        let metallic = self.metallic.clamp(0.0, 1.0);
+        let roughness = self.roughness.clamp(0.03, 1.0);
+        let alpha = roughness.powi(2);
+        let halfway = (lightv + eyev).normalize();
+        let n_dot_l = normal.dot(&lightv).max(0.0);
+        let n_dot_v = normal.dot(&eyev).max(1e-4);
+        let n_dot_h = normal.dot(&halfway).max(0.0);
+        let v_dot_h = eyev.dot(&halfway).max(0.0);
+
+        let f0 = (Color::white() * 0.04).lerp(base, metallic);
+        let fresnel = f0 + (Color::white() - f0) * (1.0 - v_dot_h).powi(5);
+
+        let alpha2 = alpha * alpha;
+        let denominator = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+        let distribution = alpha2 / (PI * denominator * denominator);
+
+        let k = (roughness + 1.0).powi(2) / 8.0;
+        let geometry = |cos: f64| cos / (cos * (1.0 - k) + k);
+        let visibility =
+            geometry(n_dot_l) * geometry(n_dot_v) / (4.0 * n_dot_l.max(1e-4) * n_dot_v);
+
+        let diffuse = (Color::white() - fresnel) * base * (1.0 - metallic);
+        let specular = fresnel * (PI * distribution * visibility);
+        (diffuse, specular)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightingComponents {
+    pub ambient: Color,
+    pub diffuse: Color,
+    pub specular: Color,
+}
+
+impl LightingComponents {
+    #[must_use]
+    pub fn total(&self) -> Color {
+        self.ambient + self.diffuse + self.specular
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::pattern::StripePattern;
-
     use super::*;
 
     #[test]
@@ -114,6 +527,122 @@ mod tests {
         assert!(equal(m.diffuse, 0.9));
         assert!(equal(m.specular, 0.9));
         assert!(equal(m.shininess, 200.0));
+        assert!(equal(m.transparency, 0.0));
+        assert!(equal(m.refractive_index, 1.0));
+        assert!(equal(m.reflective, 0.0));
+        assert!(equal(m.roughness, 0.0));
+    }
+
+    #[test]
+    fn presets() {
+        let red = Color::new(1.0, 0.0, 0.0);
+
+        let matte = Material::matte(red);
+        assert_eq!(matte.color, red);
+        assert!(equal(matte.specular, 0.0));
+        assert!(equal(matte.reflective, 0.0));
+
+        let plastic = Material::plastic(red);
+        assert_eq!(plastic.color, red);
+        assert!(plastic.specular > 0.0 && plastic.specular < 1.0);
+        assert!(equal(plastic.reflective, 0.0));
+
+        let metal = Material::metal(red);
+        assert!(metal.diffuse < metal.specular);
+        assert!(metal.shininess > plastic.shininess);
+        assert!(metal.reflective > 0.0);
+
+        let mirror = Material::mirror();
+        assert!(equal(mirror.reflective, 1.0));
+        assert!(mirror.diffuse < 0.1);
+
+        let glass = Material::glass();
+        assert!(glass.transparency > 0.0 && glass.reflective > 0.0);
+        assert!(equal(glass.refractive_index, 1.5));
+    }
+
+    #[test]
+    fn pbr_lighting() {
+        let red = Color::new(1.0, 0.2, 0.2);
+        let object = Object::default();
+        let light = PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white());
+        let eyev = Vector::new(0.0, 0.0, -1.0);
+        let components = |material: &Material, eyev: Vector| {
+            material.lighting_components(
+                &object,
+                Point::default(),
+                light,
+                eyev,
+                Vector::new(0.0, 0.0, -1.0),
+                Color::white(),
+            )
+        };
+
+        // A dielectric keeps everything Fresnel doesn't reflect as diffuse.
+        let plastic = components(&Material::pbr(red, 0.0, 1.0), eyev);
+        assert_eq!(plastic.diffuse, red * 0.96);
+
+        // Metals have no diffuse term and tint their highlights.
+        let metal = components(&Material::pbr(red, 1.0, 0.5), eyev);
+        assert_eq!(metal.diffuse, Color::black());
+        assert!(metal.specular.r > metal.specular.g);
+
+        // Smoother surfaces concentrate the highlight.
+        let grazing = Vector::new(0.0, 0.5, -1.0).normalize();
+        let (smooth, rough) = (Material::pbr(red, 0.0, 0.2), Material::pbr(red, 0.0, 0.8));
+        assert!(components(&smooth, eyev).specular.r > components(&rough, eyev).specular.r);
+        assert!(components(&smooth, grazing).specular.r < components(&rough, grazing).specular.r);
+
+        // Out-of-range roughness behaves like the nearest valid value.
+        assert_eq!(
+            components(&Material::pbr(red, 0.0, 3.0), grazing).specular,
+            components(&Material::pbr(red, 0.0, 1.0), grazing).specular
+        );
+    }
+
+    #[test]
+    fn coat_weight_follows_fresnel() {
+        let coat = Coat::default();
+        let normal = Vector::new(0.0, 0.0, -1.0);
+
+        assert!(equal(coat.weight(normal, normal), 0.04));
+        assert!(coat.weight(Vector::new(0.0, 0.99, -0.1).normalize(), normal) > 0.5);
+        assert!(equal(
+            Coat {
+                reflective: 0.0,
+                ..coat
+            }
+            .weight(normal, normal),
+            0.0
+        ));
+    }
+
+    #[test]
+    fn with_pattern() {
+        use crate::pattern::Patterned;
+        use crate::Sphere;
+
+        let scaling = Matrix::scaling(Vector::new(0.2, 0.2, 0.2));
+        let mut material =
+            Material::with_pattern(Pattern::Stripe(StripePattern::default()), scaling);
+        assert_eq!(material.pattern.get_transform(), scaling);
+
+        let object = Object::Sphere(Sphere::new(Matrix::default(), material.clone()));
+        assert_eq!(
+            material
+                .pattern
+                .color_at_object(&object, Point::new(0.3, 0.0, 0.0)),
+            Color::black()
+        );
+
+        material.set_pattern_transform(Matrix::default());
+        assert_eq!(material.pattern.get_transform(), Matrix::default());
+        assert_eq!(
+            material
+                .pattern
+                .color_at_object(&object, Point::new(0.3, 0.0, 0.0)),
+            Color::white()
+        );
     }
 
     #[test]
@@ -135,6 +664,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lighting_specular_color() {
+        let eye = Vector::new(0.0, 0.0, -1.0);
+        let normal = Vector::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point::new(0.0, 0.0, -10.0), Color::default());
+        let gold = Color::new(1.0, 0.8, 0.2);
+        let material = Material {
+            specular_color: Some(gold),
+            ..Default::default()
+        };
+
+        let components = material.lighting_components(
+            &Object::default(),
+            Point::default(),
+            light,
+            eye,
+            normal,
+            Color::white(),
+        );
+        assert_eq!(components.specular, gold * 0.9);
+        assert_eq!(components.diffuse, Color::new(0.9, 0.9, 0.9));
+
+        assert_eq!(
+            Material {
+                specular_color: None,
+                ..material
+            }
+            .lighting(
+                &Object::default(),
+                Point::default(),
+                light,
+                eye,
+                normal,
+                false
+            ),
+            Color::new(1.9, 1.9, 1.9),
+        );
+    }
+
     #[test]
     fn lighting_eye_45_degrees() {
         let eye = Vector::new(0.0, 2_f64.sqrt() / 2.0, -2_f64.sqrt() / 2.0);
@@ -262,4 +830,169 @@ mod tests {
             Color::black(),
         );
     }
+
+    #[test]
+    fn lighting_components_sum() {
+        let eye = Vector::new(0.0, 0.0, -1.0);
+        let normal = Vector::new(0.0, 0.0, -1.0);
+        let m = Material::default();
+
+        for light_position in [Point::new(0.0, 0.0, -10.0), Point::new(0.0, 10.0, -10.0)] {
+            let light = PointLight::new(light_position, Color::default());
+            let components = m.lighting_components(
+                &Object::default(),
+                Point::default(),
+                light,
+                eye,
+                normal,
+                Color::white(),
+            );
+            let total = components.ambient + components.diffuse + components.specular;
+
+            assert_eq!(
+                total,
+                m.lighting(
+                    &Object::default(),
+                    Point::default(),
+                    light,
+                    eye,
+                    normal,
+                    false
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn lighting_components_no_specular() {
+        let eye = Vector::new(0.0, 0.0, -1.0);
+        let normal = Vector::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point::new(0.0, 10.0, -10.0), Color::default());
+        let components = Material::default().lighting_components(
+            &Object::default(),
+            Point::default(),
+            light,
+            eye,
+            normal,
+            Color::white(),
+        );
+
+        assert_eq!(components.ambient, Color::new(0.1, 0.1, 0.1));
+        assert_eq!(components.diffuse, Color::new(0.6364, 0.6364, 0.6364));
+        assert_eq!(components.specular, Color::black());
+    }
+
+    #[test]
+    fn lighting_normal_tint() {
+        let red = Color::new(1.0, 0.0, 0.0);
+        let blue = Color::new(0.0, 0.0, 1.0);
+        let material = Material {
+            normal_tint: Some((red, blue)),
+            ambient: 1.0,
+            diffuse: 0.0,
+            specular: 0.0,
+            ..Default::default()
+        };
+        let normal = Vector::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point::new(0.0, 0.0, -10.0), Color::default());
+
+        assert_eq!(
+            material.lighting(
+                &Object::default(),
+                Point::default(),
+                light,
+                Vector::new(0.0, 0.0, -1.0),
+                normal,
+                false
+            ),
+            red,
+        );
+
+        assert_eq!(
+            material.lighting(
+                &Object::default(),
+                Point::default(),
+                light,
+                Vector::new(0.0, 1.0, 0.0),
+                normal,
+                false
+            ),
+            blue,
+        );
+
+        assert_eq!(
+            Material {
+                normal_tint: None,
+                ..Default::default()
+            }
+            .lighting(
+                &Object::default(),
+                Point::default(),
+                light,
+                Vector::new(0.0, 0.0, -1.0),
+                normal,
+                false
+            ),
+            Color::new(1.9, 1.9, 1.9),
+        );
+    }
+
+    #[test]
+    fn material_from_toml() {
+        let material = Material::from_toml(
+            "color = [1, 0.9, 0.9]\n\
+             ambient = 0.2\n\
+             diffuse = 0.7\n\
+             specular = 0.0\n\
+             shininess = 10\n\
+             transparency = 0.5\n\
+             pattern = \"checker\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(material.color, Color::new(1.0, 0.9, 0.9));
+        assert!(equal(material.ambient, 0.2));
+        assert!(equal(material.diffuse, 0.7));
+        assert!(equal(material.specular, 0.0));
+        assert!(equal(material.shininess, 10.0));
+        assert!(equal(material.transparency, 0.5));
+        assert_eq!(
+            material.pattern,
+            Pattern::Checker(CheckerPattern::default())
+        );
+    }
+
+    #[test]
+    fn material_from_partial_toml() {
+        let material = Material::from_toml("color = [2.0, -1.0, 0.5]\nspecular = 0.0").unwrap();
+
+        assert_eq!(material.color, Color::new(2.0, -1.0, 0.5));
+        assert!(equal(material.specular, 0.0));
+        assert!(equal(material.ambient, 0.1));
+        assert!(equal(material.diffuse, 0.9));
+        assert!(equal(material.shininess, 200.0));
+        assert_eq!(material.pattern, Pattern::None);
+    }
+
+    #[test]
+    fn material_from_bad_toml() {
+        assert_eq!(
+            Material::from_toml("glossiness = 0.5"),
+            Err(ParseError::UnknownKey("glossiness".to_string()))
+        );
+        assert_eq!(
+            Material::from_toml("color = 0.5"),
+            Err(ParseError::TypeMismatch {
+                key: "color".to_string(),
+                expected: "an array of 3 numbers"
+            })
+        );
+        assert_eq!(
+            Material::from_toml("pattern = \"marble\""),
+            Err(ParseError::UnknownValue {
+                key: "pattern".to_string(),
+                value: "marble".to_string()
+            })
+        );
+    }
 }
@@ -1,8 +1,11 @@
 #![allow(clippy::needless_range_loop)]
 
-use crate::utils::equal;
+use crate::error::RaytracerError;
+use crate::tuple::Tuple;
+use crate::utils::{equal, equal_rel};
 use crate::{Point, Vector};
 
+use std::fmt;
 use std::ops::Mul;
 
 #[derive(Debug, Clone, Copy)]
@@ -15,8 +18,16 @@ impl Matrix {
     #[allow(clippy::needless_pass_by_value)]
     #[must_use]
     pub fn new(dimension: usize, contents: Vec<f64>) -> Self {
+        Self::try_new(dimension, contents).expect("bad matrix dimension")
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn try_new(dimension: usize, contents: Vec<f64>) -> Result<Self, RaytracerError> {
         if dimension > 4 || contents.len() != dimension * dimension {
-            panic!();
+            return Err(RaytracerError::BadDimension {
+                dimension,
+                len: contents.len(),
+            });
         }
 
         let mut grid = [[0.0; 4]; 4];
@@ -27,7 +38,7 @@ impl Matrix {
             }
         }
 
-        Self { dimension, grid }
+        Ok(Self { dimension, grid })
     }
 
     #[must_use]
@@ -112,10 +123,13 @@ impl Matrix {
 
     #[must_use]
     pub fn inverse(&self) -> Matrix {
+        self.try_inverse().expect("matrix is not invertible")
+    }
+
+    pub fn try_inverse(&self) -> Result<Matrix, RaytracerError> {
         let determinant = self.determinant();
         if determinant == 0.0 {
-            //return None;
-            panic!();
+            return Err(RaytracerError::SingularMatrix);
         }
 
         let mut grid = [[0.0; 4]; 4];
@@ -125,10 +139,33 @@ impl Matrix {
             }
         }
 
-        Matrix {
+        Ok(Matrix {
             dimension: self.dimension,
             grid,
-        }
+        })
+    }
+
+    #[must_use]
+    pub fn inverse_transpose(&self) -> Matrix {
+        self.inverse().transpose()
+    }
+
+    #[must_use]
+    pub fn transform_normal(&self, normal: Vector) -> Vector {
+        (self * normal).normalize()
+    }
+}
+
+impl Matrix {
+    #[must_use]
+    pub fn approx_eq_rel(&self, other: &Self, abs_tol: f64, rel_tol: f64) -> bool {
+        self.dimension == other.dimension
+            && self
+                .grid
+                .iter()
+                .flatten()
+                .zip(other.grid.iter().flatten())
+                .all(|(a, b)| equal_rel(*a, *b, abs_tol, rel_tol))
     }
 }
 
@@ -138,6 +175,32 @@ impl Default for Matrix {
     }
 }
 
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cells: Vec<Vec<String>> = (0..self.dimension)
+            .map(|row| {
+                (0..self.dimension)
+                    .map(|col| self.get(row, col).to_string())
+                    .collect()
+            })
+            .collect();
+        let width = cells.iter().flatten().map(String::len).max().unwrap_or(0);
+
+        for (i, row) in cells.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for (j, cell) in row.iter().enumerate() {
+                if j > 0 {
+                    write!(f, "  ")?;
+                }
+                write!(f, "{cell:>width$}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl PartialEq for Matrix {
     fn eq(&self, other: &Self) -> bool {
         for row in 0..4 {
@@ -180,15 +243,7 @@ impl Mul<Vector> for Matrix {
     type Output = Vector;
 
     fn mul(self, other: Vector) -> Vector {
-        let x = other.x;
-        let y = other.y;
-        let z = other.z;
-
-        Vector {
-            x: x * self.get(0, 0) + y * self.get(0, 1) + z * self.get(0, 2),
-            y: x * self.get(1, 0) + y * self.get(1, 1) + z * self.get(1, 2),
-            z: x * self.get(2, 0) + y * self.get(2, 1) + z * self.get(2, 2),
-        }
+        &self * other
     }
 }
 
@@ -196,15 +251,7 @@ impl Mul<Vector> for &Matrix {
     type Output = Vector;
 
     fn mul(self, other: Vector) -> Vector {
-        let x = other.x;
-        let y = other.y;
-        let z = other.z;
-
-        Vector {
-            x: x * self.get(0, 0) + y * self.get(0, 1) + z * self.get(0, 2),
-            y: x * self.get(1, 0) + y * self.get(1, 1) + z * self.get(1, 2),
-            z: x * self.get(2, 0) + y * self.get(2, 1) + z * self.get(2, 2),
-        }
+        (self * Tuple::from(other)).into()
     }
 }
 
@@ -212,15 +259,7 @@ impl Mul<Point> for Matrix {
     type Output = Point;
 
     fn mul(self, other: Point) -> Point {
-        let x = other.x;
-        let y = other.y;
-        let z = other.z;
-
-        Point {
-            x: x * self.get(0, 0) + y * self.get(0, 1) + z * self.get(0, 2) + self.get(0, 3),
-            y: x * self.get(1, 0) + y * self.get(1, 1) + z * self.get(1, 2) + self.get(1, 3),
-            z: x * self.get(2, 0) + y * self.get(2, 1) + z * self.get(2, 2) + self.get(2, 3),
-        }
+        &self * other
     }
 }
 
@@ -228,21 +267,20 @@ impl Mul<Point> for &Matrix {
     type Output = Point;
 
     fn mul(self, other: Point) -> Point {
-        let x = other.x;
-        let y = other.y;
-        let z = other.z;
-
-        Point {
-            x: x * self.get(0, 0) + y * self.get(0, 1) + z * self.get(0, 2) + self.get(0, 3),
-            y: x * self.get(1, 0) + y * self.get(1, 1) + z * self.get(1, 2) + self.get(1, 3),
-            z: x * self.get(2, 0) + y * self.get(2, 1) + z * self.get(2, 2) + self.get(2, 3),
-        }
+        (self * Tuple::from(other)).into()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::EPSILON;
+
+    #[test]
+    fn display_matrix() {
+        let m = Matrix::new(2, vec![1.0, -2.5, 30.0, 4.0]);
+        assert_eq!(m.to_string(), "   1  -2.5\n  30     4");
+    }
 
     #[test]
     fn new_matrix_4() {
@@ -518,4 +556,70 @@ mod tests {
         assert_eq!(m1.inverse().inverse(), m1);
         assert_eq!(m3.inverse() * m3, Matrix::eye(4));
     }
+
+    #[test]
+    fn matrix_inverse_transpose() {
+        let m = Matrix::scaling(Vector::new(2.0, 4.0, 5.0))
+            * Matrix::translation(Vector::new(1.0, 2.0, 3.0));
+
+        #[rustfmt::skip]
+        let expected = Matrix::new(4, vec![
+            0.5, 0.0, 0.0, 0.0,
+            0.0, 0.25, 0.0, 0.0,
+            0.0, 0.0, 0.2, 0.0,
+            -1.0, -2.0, -3.0, 1.0,
+        ]);
+        assert_eq!(m.inverse_transpose(), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "matrix is not invertible")]
+    fn matrix_inverse_transpose_singular() {
+        let _ = Matrix::scaling(Vector::new(1.0, 0.0, 1.0)).inverse_transpose();
+    }
+
+    #[test]
+    fn matrix_transform_normal() {
+        let m = Matrix::translation(Vector::new(5.0, -3.0, 2.0));
+        let n = Vector::new(0.0, 0.6, 0.8);
+
+        assert_eq!(m.transform_normal(n), n);
+        assert_eq!(m.inverse_transpose().transform_normal(n), n);
+    }
+
+    #[test]
+    fn matrix_approx_eq_rel() {
+        let a = Matrix::translation(Vector::new(1e6, 0.0, 0.0));
+        let b = Matrix::translation(Vector::new(1e6 + 0.01, 0.0, 0.0));
+
+        assert_ne!(a, b);
+        assert!(a.approx_eq_rel(&b, EPSILON, 1e-7));
+        assert!(!a.approx_eq_rel(&b, EPSILON, 1e-9));
+    }
+
+    #[test]
+    fn fallible_construction_and_inverse() {
+        assert!(matches!(
+            Matrix::try_new(3, vec![1.0; 8]),
+            Err(RaytracerError::BadDimension {
+                dimension: 3,
+                len: 8
+            })
+        ));
+        assert!(matches!(
+            Matrix::try_new(5, vec![1.0; 25]),
+            Err(RaytracerError::BadDimension { .. })
+        ));
+        assert_eq!(
+            Matrix::try_new(2, vec![1.0; 4]).unwrap(),
+            Matrix::new(2, vec![1.0; 4])
+        );
+
+        let singular = Matrix::new(2, vec![1.0, 2.0, 2.0, 4.0]);
+        assert!(matches!(
+            singular.try_inverse(),
+            Err(RaytracerError::SingularMatrix)
+        ));
+        assert_eq!(Matrix::eye(4).try_inverse().unwrap(), Matrix::eye(4));
+    }
 }
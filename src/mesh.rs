@@ -0,0 +1,223 @@
+use crate::shape::next_id;
+use crate::smooth_triangle::intersect_face;
+use crate::transformations::Transformable;
+use crate::{
+    BoundingBox, Intersection, Material, Matrix, Object, Point, Ray, Shape, SmoothTriangle, Vector,
+};
+use std::sync::Arc;
+
+// Triangles sharing one vertex buffer. The buffers sit behind `Arc`s, so the
+// clones stored in worlds and intersections don't copy them.
+#[derive(Debug, Clone)]
+pub struct Mesh {
+    transform: Matrix,
+    material: Material,
+    vertices: Arc<[Point]>,
+    normals: Option<Arc<[Vector]>>,
+    faces: Arc<[[usize; 3]]>,
+    bounds: BoundingBox,
+    pub(crate) id: usize,
+}
+
+impl Mesh {
+    #[must_use]
+    pub fn new(vertices: Vec<Point>, faces: Vec<[usize; 3]>) -> Self {
+        assert!(
+            faces.iter().flatten().all(|&index| index < vertices.len()),
+            "face index out of range"
+        );
+
+        let mut bounds = BoundingBox::empty();
+        for &vertex in &vertices {
+            bounds.add_point(vertex);
+        }
+
+        Self {
+            transform: Matrix::default(),
+            material: Material::default(),
+            vertices: vertices.into(),
+            normals: None,
+            faces: faces.into(),
+            bounds,
+            id: next_id(),
+        }
+    }
+
+    // Per-vertex normals, interpolated across each face.
+    #[must_use]
+    pub fn with_normals(mut self, normals: Vec<Vector>) -> Self {
+        assert_eq!(normals.len(), self.vertices.len(), "one normal per vertex");
+        self.normals = Some(normals.into());
+        self
+    }
+
+    #[must_use]
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    #[must_use]
+    pub fn normals(&self) -> Option<&[Vector]> {
+        self.normals.as_deref()
+    }
+
+    #[must_use]
+    pub fn faces(&self) -> &[[usize; 3]] {
+        &self.faces
+    }
+
+    // The face as a standalone triangle; flat faces use their winding normal.
+    #[must_use]
+    pub fn triangle(&self, face: usize) -> SmoothTriangle {
+        let [a, b, c] = self.faces[face];
+        let points = [self.vertices[a], self.vertices[b], self.vertices[c]];
+        let normals = if let Some(normals) = &self.normals {
+            [normals[a], normals[b], normals[c]]
+        } else {
+            let normal = (points[1] - points[0])
+                .cross(&(points[2] - points[0]))
+                .normalize();
+            [normal; 3]
+        };
+
+        SmoothTriangle::mesh_face(points, normals, self.transform, &self.material, self.id)
+    }
+}
+
+impl Default for Mesh {
+    fn default() -> Self {
+        Self::new(Vec::new(), Vec::new())
+    }
+}
+
+impl PartialEq for Mesh {
+    fn eq(&self, other: &Self) -> bool {
+        self.transform == other.transform
+            && self.material == other.material
+            && self.vertices == other.vertices
+            && self.normals == other.normals
+            && self.faces == other.faces
+    }
+}
+
+impl Transformable for Mesh {
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Shape for Mesh {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn get_material(&self) -> Material {
+        self.material.clone()
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    // Hits resolve to the struck face so shading can interpolate its normals.
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection> {
+        if !self.bounds.intersects_ray(ray) {
+            return Vec::new();
+        }
+
+        let mut xs = Vec::new();
+        for (face, &[a, b, c]) in self.faces.iter().enumerate() {
+            let p1 = self.vertices[a];
+            let hit = intersect_face(p1, self.vertices[b] - p1, self.vertices[c] - p1, ray);
+            if let Some((t, u, v)) = hit {
+                xs.push(Intersection::with_uv(
+                    t,
+                    &Object::SmoothTriangle(self.triangle(face)),
+                    u,
+                    v,
+                ));
+            }
+        }
+        xs
+    }
+
+    // Only reached without a hit record; a mesh has no single normal.
+    fn local_normal_at(&self, _point: Point) -> Vector {
+        Vector::new(0.0, 1.0, 0.0)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::equal;
+    use crate::vector;
+
+    // Unit square in the z = 0 plane, split along its diagonal.
+    fn square() -> Mesh {
+        Mesh::new(
+            vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(1.0, 1.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+            ],
+            vec![[0, 1, 2], [0, 2, 3]],
+        )
+    }
+
+    #[test]
+    fn mesh_hits_resolve_to_faces() {
+        let mut mesh = Object::Mesh(square());
+        mesh.set_transform(Matrix::translation(Vector::new(0.0, 0.0, 5.0)));
+
+        let r = Ray::new(Point::new(0.25, 0.75, 0.0), vector::Z);
+        let xs = r.intersect(&mesh);
+        assert_eq!(xs.len(), 1);
+        assert!(equal(xs[0].t, 5.0));
+        assert!(xs[0].object.same_object(&mesh));
+        assert_eq!(xs[0].object.get_transform(), mesh.get_transform());
+
+        let Object::SmoothTriangle(face) = &xs[0].object else {
+            panic!("expected a triangle");
+        };
+        assert_eq!(face.p3, Point::new(0.0, 1.0, 0.0));
+        assert_eq!(
+            xs[0].object.normal_at_uv(r.position(xs[0].t), xs[0].uv),
+            vector::Z
+        );
+
+        let r = Ray::new(Point::new(1.5, 0.5, 0.0), vector::Z);
+        assert!(r.intersect(&mesh).is_empty());
+    }
+
+    #[test]
+    fn mesh_interpolates_vertex_normals() {
+        let mesh = square().with_normals(vec![
+            vector::Z,
+            vector::Z,
+            Vector::new(1.0, 0.0, 1.0).normalize(),
+            vector::Z,
+        ]);
+        let xs = mesh.local_intersect(&Ray::new(Point::new(0.5, 0.5, -1.0), vector::Z));
+
+        // The diagonal is shared, so both faces report the hit.
+        assert_eq!(xs.len(), 2);
+        let normal = xs[0]
+            .object
+            .normal_at_uv(Point::new(0.5, 0.5, 0.0), xs[0].uv);
+        assert!(normal.x > 0.0 && normal.z > 0.0);
+        assert_eq!(
+            mesh.bounds(),
+            BoundingBox::new(Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 0.0))
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    Syntax { line: usize },
+    UnknownKey(String),
+    TypeMismatch { key: String, expected: &'static str },
+    UnknownValue { key: String, value: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Syntax { line } => write!(f, "syntax error on line {line}"),
+            ParseError::UnknownKey(key) => write!(f, "unknown key `{key}`"),
+            ParseError::TypeMismatch { key, expected } => {
+                write!(f, "key `{key}` expects {expected}")
+            }
+            ParseError::UnknownValue { key, value } => {
+                write!(f, "unknown value `{value}` for key `{key}`")
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Number(f64),
+    Str(String),
+    Array(Vec<f64>),
+}
+
+impl Value {
+    pub(crate) fn number(&self, key: &str) -> Result<f64, ParseError> {
+        match self {
+            Value::Number(n) => Ok(*n),
+            _ => Err(mismatch(key, "a number")),
+        }
+    }
+
+    pub(crate) fn string(&self, key: &str) -> Result<&str, ParseError> {
+        match self {
+            Value::Str(s) => Ok(s),
+            _ => Err(mismatch(key, "a string")),
+        }
+    }
+
+    pub(crate) fn triple(&self, key: &str) -> Result<(f64, f64, f64), ParseError> {
+        match self {
+            Value::Array(a) if a.len() == 3 => Ok((a[0], a[1], a[2])),
+            _ => Err(mismatch(key, "an array of 3 numbers")),
+        }
+    }
+}
+
+fn mismatch(key: &str, expected: &'static str) -> ParseError {
+    ParseError::TypeMismatch {
+        key: key.to_string(),
+        expected,
+    }
+}
+
+// Drops a trailing `#` comment, leaving any `#` inside a string alone.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+pub(crate) fn parse_toml(s: &str) -> Result<Vec<(String, Value)>, ParseError> {
+    let mut entries = Vec::new();
+
+    for (index, line) in s.lines().enumerate() {
+        let syntax = ParseError::Syntax { line: index + 1 };
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (key, value) = line.split_once('=').ok_or_else(|| syntax.clone())?;
+        let key = key.trim();
+        let value = value.trim();
+        if key.is_empty() {
+            return Err(syntax);
+        }
+
+        let value = if let Some(inner) = value.strip_prefix('"') {
+            Value::Str(inner.strip_suffix('"').ok_or(syntax)?.to_string())
+        } else if let Some(inner) = value.strip_prefix('[') {
+            let inner = inner.strip_suffix(']').ok_or_else(|| syntax.clone())?;
+            let numbers: Result<Vec<f64>, _> =
+                inner.split(',').map(|n| n.trim().parse::<f64>()).collect();
+            Value::Array(numbers.map_err(|_| mismatch(key, "an array of numbers"))?)
+        } else {
+            Value::Number(value.parse().map_err(|_| mismatch(key, "a number"))?)
+        };
+
+        entries.push((key.to_string(), value));
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_values() {
+        let entries = parse_toml(
+            "# a comment\ncolor = [1, 0.9, 0.9]\n\nspecular = 0.0 # trailing\npattern = \"checker\"",
+        )
+        .unwrap();
+
+        assert_eq!(
+            entries,
+            vec![
+                ("color".to_string(), Value::Array(vec![1.0, 0.9, 0.9])),
+                ("specular".to_string(), Value::Number(0.0)),
+                ("pattern".to_string(), Value::Str("checker".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn hash_inside_string() {
+        assert_eq!(
+            parse_toml("name = \"a#b\" # comment").unwrap(),
+            vec![("name".to_string(), Value::Str("a#b".to_string()))]
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse_toml("color = [1, 0.9, 0.9]\nspecular"),
+            Err(ParseError::Syntax { line: 2 })
+        );
+        assert_eq!(
+            parse_toml("specular = high"),
+            Err(ParseError::TypeMismatch {
+                key: "specular".to_string(),
+                expected: "a number"
+            })
+        );
+    }
+}
@@ -1,53 +1,164 @@
 #![allow(clippy::module_name_repetitions)]
 
+use crate::error::RaytracerError;
 use crate::transformations::Transformable;
-use crate::{Color, Matrix, Object, Point};
+use crate::utils::EPSILON;
+use crate::uv::{cube_face, UvMapping};
+use crate::{Canvas, Color, Matrix, Object, Point, Shape, Vector};
+use std::fmt;
+use std::sync::Arc;
 
 pub trait Patterned: Transformable {
     #[must_use]
     fn color_at(&self, point: Point) -> Color;
 
+    #[must_use]
+    fn color_at_filtered(&self, point: Point, _half_widths: Vector) -> Color {
+        self.color_at(point)
+    }
+
     #[must_use]
     fn color_at_object(&self, object: &Object, point: Point) -> Color {
-        let object_point = object.get_transform().inverse() * point;
-        let pattern_point = self.get_transform().inverse() * object_point;
+        let object_point = object.world_to_object(point);
+        let material_point = object.get_material().material_transform.inverse() * object_point;
+        let pattern_point = self.get_transform().inverse() * material_point;
         self.color_at(pattern_point)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Pattern {
     None,
+    Solid(Color),
     Stripe(StripePattern),
+    Gradient(GradientPattern),
+    Ring(RingPattern),
+    Checker(CheckerPattern),
+    Uv(UvPattern),
+    TextureMap(TextureMapPattern),
+    CubeMap(Box<CubeMapPattern>),
+    Blend(BlendPattern),
+    Mask(MaskPattern),
+    Custom(CustomPattern),
+    Test(TestPattern),
 }
 
 impl Transformable for Pattern {
     #[must_use]
     fn get_transform(&self) -> Matrix {
         match self {
-            Pattern::None => Matrix::default(),
+            Pattern::None | Pattern::Solid(_) | Pattern::Uv(_) => Matrix::default(),
             Pattern::Stripe(pattern) => pattern.get_transform(),
+            Pattern::Gradient(pattern) => pattern.get_transform(),
+            Pattern::Ring(pattern) => pattern.get_transform(),
+            Pattern::Checker(pattern) => pattern.get_transform(),
+            Pattern::TextureMap(pattern) => pattern.get_transform(),
+            Pattern::CubeMap(pattern) => pattern.get_transform(),
+            Pattern::Test(pattern) => pattern.get_transform(),
+            Pattern::Blend(pattern) => pattern.get_transform(),
+            Pattern::Mask(pattern) => pattern.get_transform(),
+            Pattern::Custom(pattern) => pattern.get_transform(),
         }
     }
 
     fn set_transform(&mut self, transform: Matrix) {
         match self {
-            Pattern::None => {}
+            Pattern::None | Pattern::Solid(_) | Pattern::Uv(_) => {}
             Pattern::Stripe(pattern) => pattern.set_transform(transform),
+            Pattern::Gradient(pattern) => pattern.set_transform(transform),
+            Pattern::Ring(pattern) => pattern.set_transform(transform),
+            Pattern::Checker(pattern) => pattern.set_transform(transform),
+            Pattern::TextureMap(pattern) => pattern.set_transform(transform),
+            Pattern::CubeMap(pattern) => pattern.set_transform(transform),
+            Pattern::Test(pattern) => pattern.set_transform(transform),
+            Pattern::Blend(pattern) => pattern.set_transform(transform),
+            Pattern::Mask(pattern) => pattern.set_transform(transform),
+            Pattern::Custom(pattern) => pattern.set_transform(transform),
         }
     }
 }
 
 impl Patterned for Pattern {
+    #[must_use]
+    fn color_at_filtered(&self, point: Point, half_widths: Vector) -> Color {
+        match self {
+            Pattern::Checker(pattern) => pattern.color_at_filtered(point, half_widths),
+            Pattern::Custom(pattern) => pattern.color_at_filtered(point, half_widths),
+            _ => self.color_at(point),
+        }
+    }
+
     #[must_use]
     fn color_at(&self, point: Point) -> Color {
         match self {
-            Pattern::None => panic!(),
+            // Materials show their own color when unset, so this is only
+            // reached through nested patterns or direct calls.
+            Pattern::None => Color::black(),
+            Pattern::Solid(color) => *color,
             Pattern::Stripe(pattern) => pattern.color_at(point),
+            Pattern::Gradient(pattern) => pattern.color_at(point),
+            Pattern::Ring(pattern) => pattern.color_at(point),
+            Pattern::Checker(pattern) => pattern.color_at(point),
+            Pattern::TextureMap(pattern) => pattern.color_at(point),
+            Pattern::CubeMap(pattern) => pattern.color_at(point),
+            Pattern::Test(pattern) => pattern.color_at(point),
+            Pattern::Blend(pattern) => pattern.color_at(point),
+            Pattern::Mask(pattern) => pattern.color_at(point),
+            Pattern::Custom(pattern) => pattern.color_at(point),
+            Pattern::Uv(pattern) => {
+                pattern.color_at_uv(point.x.rem_euclid(1.0), point.z.rem_euclid(1.0))
+            }
         }
     }
 }
 
+impl Pattern {
+    pub fn try_color_at(&self, point: Point) -> Result<Color, RaytracerError> {
+        match self {
+            Pattern::None => Err(RaytracerError::UninitializedPattern),
+            _ => Ok(self.color_at(point)),
+        }
+    }
+
+    #[must_use]
+    pub fn color_at_object_uv(&self, object: &Object, point: Point) -> Color {
+        if let Pattern::Uv(pattern) = self {
+            let object_point = object.world_to_object(point);
+            let object_point = object.get_material().material_transform.inverse() * object_point;
+            if let Some((u, v)) = object.uv_at(object_point) {
+                return pattern.color_at_uv(u, v);
+            }
+        }
+
+        self.color_at_object(object, point)
+    }
+
+    #[must_use]
+    pub fn color_at_object_filtered(
+        &self,
+        object: &Object,
+        point: Point,
+        normal: Vector,
+        radius: f64,
+    ) -> Color {
+        if radius <= 0.0 || matches!(self, Pattern::Uv(_)) {
+            return self.color_at_object_uv(object, point);
+        }
+
+        let to_pattern = self.get_transform().inverse()
+            * object.get_material().material_transform.inverse()
+            * object.get_transform().inverse();
+        let (u, v) = normal.orthonormal_basis();
+        let (u, v) = (to_pattern * (u * radius), to_pattern * (v * radius));
+        let half_widths = Vector::new(
+            u.x.abs() + v.x.abs(),
+            u.y.abs() + v.y.abs(),
+            u.z.abs() + v.z.abs(),
+        );
+        self.color_at_filtered(to_pattern * point, half_widths)
+    }
+}
+
 impl Default for Pattern {
     fn default() -> Self {
         Self::None
@@ -228,19 +339,457 @@ impl Transformable for CheckerPattern {
 impl Patterned for CheckerPattern {
     #[must_use]
     fn color_at(&self, point: Point) -> Color {
-        #[allow(clippy::cast_possible_truncation)]
-        if (point.x.floor() as isize + point.y.floor() as isize + point.z.floor() as isize) % 2 == 0
-        {
+        let sum = snapped_floor(point.x) + snapped_floor(point.y) + snapped_floor(point.z);
+        if sum.rem_euclid(2.0) < 1.0 {
             self.color1
         } else {
             self.color2
         }
     }
+
+    #[must_use]
+    fn color_at_filtered(&self, point: Point, half_widths: Vector) -> Color {
+        if half_widths.x < EPSILON && half_widths.y < EPSILON && half_widths.z < EPSILON {
+            return self.color_at(point);
+        }
+
+        let weight = square_wave_average(point.x, half_widths.x)
+            * square_wave_average(point.y, half_widths.y)
+            * square_wave_average(point.z, half_widths.z);
+        self.color2.lerp(self.color1, f64::midpoint(1.0, weight))
+    }
+}
+
+// Box-filtered average of a wave that is +1 on even cells and -1 on odd ones.
+fn square_wave_average(x: f64, half_width: f64) -> f64 {
+    if half_width < EPSILON {
+        return if snapped_floor(x).rem_euclid(2.0) < 1.0 {
+            1.0
+        } else {
+            -1.0
+        };
+    }
+
+    let integral = |x: f64| {
+        let f = x.rem_euclid(2.0);
+        if f < 1.0 {
+            f
+        } else {
+            2.0 - f
+        }
+    };
+    (integral(x + half_width) - integral(x - half_width)) / (2.0 * half_width)
+}
+
+// Evaluates a pattern nested in another, whose space `point` is in.
+fn nested_color_at(pattern: &Pattern, point: Point) -> Color {
+    pattern.color_at(pattern.get_transform().inverse() * point)
+}
+
+// Mixes two patterns, weighting the second by `factor`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlendPattern {
+    pub a: Arc<Pattern>,
+    pub b: Arc<Pattern>,
+    pub factor: f64,
+    pub transform: Matrix,
+}
+
+impl BlendPattern {
+    #[must_use]
+    pub fn new(a: Pattern, b: Pattern, factor: f64) -> Self {
+        Self {
+            a: Arc::new(a),
+            b: Arc::new(b),
+            factor,
+            transform: Matrix::default(),
+        }
+    }
+}
+
+impl Transformable for BlendPattern {
+    #[must_use]
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Patterned for BlendPattern {
+    #[must_use]
+    fn color_at(&self, point: Point) -> Color {
+        nested_color_at(&self.a, point).lerp(nested_color_at(&self.b, point), self.factor)
+    }
+}
+
+// Picks `a` where the mask is bright and `b` where it is dark. A stripe or
+// checker mask puts patterns in the slots of a stripe or checkerboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaskPattern {
+    pub mask: Arc<Pattern>,
+    pub a: Arc<Pattern>,
+    pub b: Arc<Pattern>,
+    pub transform: Matrix,
+}
+
+impl MaskPattern {
+    #[must_use]
+    pub fn new(mask: Pattern, a: Pattern, b: Pattern) -> Self {
+        Self {
+            mask: Arc::new(mask),
+            a: Arc::new(a),
+            b: Arc::new(b),
+            transform: Matrix::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn stripe(a: Pattern, b: Pattern) -> Self {
+        Self::new(Pattern::Stripe(StripePattern::default()), a, b)
+    }
+
+    #[must_use]
+    pub fn checker(a: Pattern, b: Pattern) -> Self {
+        Self::new(Pattern::Checker(CheckerPattern::default()), a, b)
+    }
+}
+
+impl Transformable for MaskPattern {
+    #[must_use]
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Patterned for MaskPattern {
+    #[must_use]
+    fn color_at(&self, point: Point) -> Color {
+        if nested_color_at(&self.mask, point).luminance() >= 0.5 {
+            nested_color_at(&self.a, point)
+        } else {
+            nested_color_at(&self.b, point)
+        }
+    }
+}
+
+// Pattern types defined outside the crate. The pattern is shared between
+// clones, so the wrapper owns the transform, starting from the pattern's.
+#[derive(Clone)]
+pub struct CustomPattern {
+    pattern: Arc<dyn Patterned + Send + Sync>,
+    pub transform: Matrix,
+}
+
+impl CustomPattern {
+    #[must_use]
+    pub fn new(pattern: impl Patterned + Send + Sync + 'static) -> Self {
+        Self {
+            transform: pattern.get_transform(),
+            pattern: Arc::new(pattern),
+        }
+    }
+
+    pub(crate) fn pattern_ptr(&self) -> *const () {
+        Arc::as_ptr(&self.pattern).cast()
+    }
+}
+
+impl fmt::Debug for CustomPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CustomPattern")
+            .field("transform", &self.transform)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for CustomPattern {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.pattern, &other.pattern) && self.transform == other.transform
+    }
+}
+
+impl Transformable for CustomPattern {
+    #[must_use]
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Patterned for CustomPattern {
+    #[must_use]
+    fn color_at(&self, point: Point) -> Color {
+        self.pattern.color_at(point)
+    }
+
+    #[must_use]
+    fn color_at_filtered(&self, point: Point, half_widths: Vector) -> Color {
+        self.pattern.color_at_filtered(point, half_widths)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TestPattern {
+    pub transform: Matrix,
+}
+
+impl Transformable for TestPattern {
+    #[must_use]
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Patterned for TestPattern {
+    #[must_use]
+    fn color_at(&self, point: Point) -> Color {
+        Color::new(point.x, point.y, point.z)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UvPattern {
+    Checkers(UvCheckers),
+    AlignCheck(UvAlignCheck),
+    Image(UvImage),
+}
+
+impl UvPattern {
+    #[must_use]
+    pub fn color_at_uv(&self, u: f64, v: f64) -> Color {
+        match self {
+            UvPattern::Checkers(pattern) => pattern.color_at_uv(u, v),
+            UvPattern::AlignCheck(pattern) => pattern.color_at_uv(u, v),
+            UvPattern::Image(pattern) => pattern.color_at_uv(u, v),
+        }
+    }
+}
+
+// Applies a UV pattern to any shape through an explicit projection, rather
+// than the one the shape itself provides.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureMapPattern {
+    pub uv_pattern: UvPattern,
+    pub mapping: UvMapping,
+    pub transform: Matrix,
+}
+
+impl TextureMapPattern {
+    #[must_use]
+    pub fn new(uv_pattern: UvPattern, mapping: UvMapping) -> Self {
+        Self {
+            uv_pattern,
+            mapping,
+            transform: Matrix::default(),
+        }
+    }
+}
+
+impl Transformable for TextureMapPattern {
+    #[must_use]
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Patterned for TextureMapPattern {
+    #[must_use]
+    fn color_at(&self, point: Point) -> Color {
+        let (u, v) = self.mapping.map(point);
+        self.uv_pattern.color_at_uv(u, v)
+    }
+}
+
+// One UV pattern per face of the unit cube, in `cube_face` order:
+// +X, -X, +Y, -Y, +Z, -Z. Each face sees u to the right and v up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubeMapPattern {
+    pub faces: [UvPattern; 6],
+    pub transform: Matrix,
+}
+
+impl CubeMapPattern {
+    #[must_use]
+    pub fn new(faces: [UvPattern; 6]) -> Self {
+        Self {
+            faces,
+            transform: Matrix::default(),
+        }
+    }
+}
+
+impl Transformable for CubeMapPattern {
+    #[must_use]
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Patterned for CubeMapPattern {
+    #[must_use]
+    fn color_at(&self, point: Point) -> Color {
+        let (face, u, v) = cube_face(point - Point::default());
+        self.faces[face].color_at_uv(u, 1.0 - v)
+    }
+}
+
+// Marks each corner of a face, to check how faces line up with each other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UvAlignCheck {
+    pub main: Color,
+    pub upper_left: Color,
+    pub upper_right: Color,
+    pub bottom_left: Color,
+    pub bottom_right: Color,
+}
+
+impl UvAlignCheck {
+    #[must_use]
+    pub fn new(
+        main: Color,
+        upper_left: Color,
+        upper_right: Color,
+        bottom_left: Color,
+        bottom_right: Color,
+    ) -> Self {
+        Self {
+            main,
+            upper_left,
+            upper_right,
+            bottom_left,
+            bottom_right,
+        }
+    }
+
+    #[must_use]
+    pub fn color_at_uv(&self, u: f64, v: f64) -> Color {
+        match (u < 0.2, u > 0.8, v < 0.2, v > 0.8) {
+            (true, _, _, true) => self.upper_left,
+            (_, true, _, true) => self.upper_right,
+            (true, _, true, _) => self.bottom_left,
+            (_, true, true, _) => self.bottom_right,
+            _ => self.main,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UvCheckers {
+    pub width: f64,
+    pub height: f64,
+    pub color1: Color,
+    pub color2: Color,
+}
+
+impl UvCheckers {
+    #[must_use]
+    pub fn new(width: f64, height: f64, color1: Color, color2: Color) -> Self {
+        Self {
+            width,
+            height,
+            color1,
+            color2,
+        }
+    }
+
+    #[must_use]
+    pub fn color_at_uv(&self, u: f64, v: f64) -> Color {
+        let sum = (u * self.width).floor() + (v * self.height).floor();
+        if sum.rem_euclid(2.0) < 1.0 {
+            self.color1
+        } else {
+            self.color2
+        }
+    }
+}
+
+// A loaded image stretched over the unit square, v up. With `linearize` the
+// texels are taken as sRGB-encoded and decoded before shading.
+#[derive(Debug, Clone)]
+pub struct UvImage {
+    canvas: Arc<Canvas>,
+    pub linearize: bool,
+}
+
+impl UvImage {
+    #[must_use]
+    pub fn new(canvas: Canvas, linearize: bool) -> Self {
+        Self {
+            canvas: Arc::new(canvas),
+            linearize,
+        }
+    }
+
+    #[must_use]
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    #[must_use]
+    pub fn color_at_uv(&self, u: f64, v: f64) -> Color {
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        if width == 0 || height == 0 {
+            return Color::black();
+        }
+
+        let x = (u.clamp(0.0, 1.0) * (width - 1) as f64).round() as usize;
+        let y = ((1.0 - v.clamp(0.0, 1.0)) * (height - 1) as f64).round() as usize;
+        let color = *self.canvas.pixel_at(x, y);
+        if self.linearize {
+            color.srgb_to_linear()
+        } else {
+            color
+        }
+    }
+}
+
+impl PartialEq for UvImage {
+    fn eq(&self, other: &Self) -> bool {
+        self.linearize == other.linearize
+            && (Arc::ptr_eq(&self.canvas, &other.canvas) || self.canvas == other.canvas)
+    }
+}
+
+fn snapped_floor(x: f64) -> f64 {
+    let nearest = x.round();
+    if (x - nearest).abs() < EPSILON {
+        nearest
+    } else {
+        x.floor()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{vector, Plane};
 
     #[test]
     fn default_striped_pattern() {
@@ -364,4 +913,396 @@ mod tests {
         assert_eq!(pattern.color_at(Point::new(-1.0, -1.0, -1.0)), Color::black());
         assert_eq!(pattern.color_at(Point::new(-1.1, -1.1, 0.0)), Color::white());
     }
+
+    #[test]
+    fn checker_filtered() {
+        let pattern = CheckerPattern::default();
+        for point in [
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(0.0, 0.9, 0.9),
+            Point::new(1.0, 0.0, 1.0),
+            Point::new(0.0, -0.1, 0.0),
+            Point::new(-1.0, -1.0, -1.0),
+            Point::new(-1.1, -1.1, 0.0),
+        ] {
+            assert_eq!(
+                pattern.color_at_filtered(point, Vector::new(0.0, 0.0, 0.0)),
+                pattern.color_at(point)
+            );
+        }
+
+        let plane = Object::Plane(Plane::default());
+        let pattern = Pattern::Checker(pattern);
+        let point = Point::new(100.0, 0.0, 0.5);
+        assert_eq!(
+            pattern.color_at_object_filtered(&plane, point, vector::Y, 0.0),
+            pattern.color_at_object(&plane, point)
+        );
+        assert_eq!(
+            pattern.color_at_object_filtered(&plane, point, vector::Y, 0.5),
+            Color::new(0.5, 0.5, 0.5)
+        );
+        assert_eq!(
+            pattern.color_at_object_filtered(&plane, Point::new(100.5, 0.0, 0.5), vector::Y, 0.1),
+            Color::white()
+        );
+    }
+
+    #[test]
+    fn checker_far_from_origin() {
+        let pattern = CheckerPattern::default();
+        let base = 1e6;
+        assert_eq!(
+            pattern.color_at(Point::new(base + 1e-5, 0.0, 0.0)),
+            Color::white()
+        );
+        assert_eq!(
+            pattern.color_at(Point::new(base - 1e-5, 0.0, 0.0)),
+            Color::white()
+        );
+        assert_eq!(
+            pattern.color_at(Point::new(base + 0.5, 0.0, 0.0)),
+            Color::white()
+        );
+        assert_eq!(
+            pattern.color_at(Point::new(base - 0.5, 0.0, 0.0)),
+            Color::black()
+        );
+        assert_eq!(
+            pattern.color_at(Point::new(base + 1.5, 0.0, 0.0)),
+            Color::black()
+        );
+        assert_eq!(
+            pattern.color_at(Point::new(base + 1e-5, 0.0, -base - 1e-5)),
+            Color::white()
+        );
+    }
+
+    #[test]
+    fn test_pattern_transforms() {
+        use crate::{Material, Sphere};
+
+        let mut pattern = TestPattern::default();
+        assert_eq!(pattern.get_transform(), Matrix::default());
+        pattern.set_transform(Matrix::translation(Vector::new(1.0, 2.0, 3.0)));
+        assert_eq!(
+            pattern.get_transform(),
+            Matrix::translation(Vector::new(1.0, 2.0, 3.0))
+        );
+
+        let scaling = Matrix::scaling(Vector::new(2.0, 2.0, 2.0));
+        let scaled = Object::Sphere(Sphere::new(scaling, Material::default()));
+        let pattern = Pattern::Test(TestPattern::default());
+        assert_eq!(
+            pattern.color_at_object(&scaled, Point::new(2.0, 3.0, 4.0)),
+            Color::new(1.0, 1.5, 2.0)
+        );
+
+        let plain = Object::Sphere(Sphere::default());
+        let scaled_pattern = Pattern::Test(TestPattern { transform: scaling });
+        assert_eq!(
+            scaled_pattern.color_at_object(&plain, Point::new(2.0, 3.0, 4.0)),
+            Color::new(1.0, 1.5, 2.0)
+        );
+
+        let translated_pattern = Pattern::Test(TestPattern {
+            transform: Matrix::translation(Vector::new(0.5, 1.0, 1.5)),
+        });
+        assert_eq!(
+            translated_pattern.color_at_object(&scaled, Point::new(2.5, 3.0, 3.5)),
+            Color::new(0.75, 0.5, 0.25)
+        );
+    }
+
+    #[test]
+    fn stripe_with_material_transform() {
+        use crate::{Material, Sphere, Vector};
+
+        let scaling = Matrix::scaling(Vector::new(2.0, 2.0, 2.0));
+        let point = Point::new(1.5, 0.0, 0.0);
+
+        let plain = Object::Sphere(Sphere::default());
+        let pattern = Pattern::Stripe(StripePattern::default());
+        assert_eq!(pattern.color_at_object(&plain, point), Color::black());
+
+        let mut scaled_pattern = pattern.clone();
+        scaled_pattern.set_transform(scaling);
+        assert_eq!(
+            scaled_pattern.color_at_object(&plain, point),
+            Color::white()
+        );
+
+        let material = Material {
+            material_transform: scaling,
+            ..Material::default()
+        };
+        let object = Object::Sphere(Sphere::new(Matrix::default(), material));
+        assert_eq!(pattern.color_at_object(&object, point), Color::white());
+    }
+
+    #[test]
+    fn uv_checkers_on_sphere() {
+        use crate::Sphere;
+
+        let sphere = Object::Sphere(Sphere::default());
+        let pattern = Pattern::Uv(UvPattern::Checkers(UvCheckers::new(
+            16.0,
+            8.0,
+            Color::black(),
+            Color::white(),
+        )));
+        let checker = Pattern::Checker(CheckerPattern::default());
+
+        let cases = [
+            (Point::new(0.4315, 0.4670, 0.7719), Color::white()),
+            (Point::new(-0.9654, 0.2552, -0.0534), Color::black()),
+            (Point::new(-0.5734, -0.2162, -0.7903), Color::white()),
+            (Point::new(0.7688, -0.1470, 0.6223), Color::black()),
+        ];
+        for (point, color) in cases {
+            assert_eq!(pattern.color_at_object_uv(&sphere, point), color);
+        }
+
+        for (point, _) in &cases[1..3] {
+            assert_ne!(
+                pattern.color_at_object_uv(&sphere, *point),
+                checker.color_at_object_uv(&sphere, *point)
+            );
+        }
+    }
+
+    #[test]
+    fn image_texture_linearizes() {
+        use crate::Material;
+
+        let texture = Canvas::from_ppm(b"P3\n2 1\n255\n128 128 128 255 0 0\n", false).unwrap();
+        let plane = Object::Plane(Plane::default());
+        let color_at = |linearize, x| {
+            let image = UvPattern::Image(UvImage::new(texture.clone(), linearize));
+            let pattern = Pattern::TextureMap(TextureMapPattern::new(image, UvMapping::Planar));
+            Material::with_pattern(pattern, Matrix::default())
+                .surface_color(&plane, Point::new(x, 0.0, 0.5))
+        };
+
+        let gray = |c| Color::new(c, c, c);
+        assert!(color_at(false, 0.25).approx_eq(&gray(0.502), 1e-3));
+        assert!(color_at(true, 0.25).approx_eq(&gray(0.216), 1e-3));
+        assert_eq!(color_at(true, 0.75), Color::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn texture_map_on_cube() {
+        use crate::{Cube, Sphere};
+
+        let checkers =
+            UvPattern::Checkers(UvCheckers::new(2.0, 2.0, Color::black(), Color::white()));
+        let pattern = Pattern::TextureMap(TextureMapPattern::new(checkers, UvMapping::Cubic));
+        let cube = Object::Cube(Cube::default());
+
+        // Every face gets the same two-by-two board.
+        let cases = [
+            (Point::new(-0.5, 0.5, 1.0), Color::white()),
+            (Point::new(0.5, 0.5, 1.0), Color::black()),
+            (Point::new(1.0, -0.5, 0.5), Color::black()),
+            (Point::new(-0.5, 1.0, 0.5), Color::black()),
+        ];
+        for (point, color) in cases {
+            assert_eq!(pattern.color_at_object(&cube, point), color);
+        }
+
+        // The mapping, not the shape, decides the projection.
+        let sphere = Object::Sphere(Sphere::default());
+        assert_eq!(
+            pattern.color_at_object(&sphere, Point::new(0.5, 0.5, 1.0)),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn align_check_pattern() {
+        let pattern = UvAlignCheck::new(
+            Color::white(),
+            Color::new(1.0, 0.0, 0.0),
+            Color::new(1.0, 1.0, 0.0),
+            Color::new(0.0, 1.0, 0.0),
+            Color::new(0.0, 1.0, 1.0),
+        );
+        let cases = [
+            (0.5, 0.5, pattern.main),
+            (0.1, 0.9, pattern.upper_left),
+            (0.9, 0.9, pattern.upper_right),
+            (0.1, 0.1, pattern.bottom_left),
+            (0.9, 0.1, pattern.bottom_right),
+        ];
+        for (u, v, color) in cases {
+            assert_eq!(pattern.color_at_uv(u, v), color);
+        }
+    }
+
+    #[test]
+    fn cube_map_faces_line_up() {
+        let red = Color::new(1.0, 0.0, 0.0);
+        let yellow = Color::new(1.0, 1.0, 0.0);
+        let brown = Color::new(1.0, 0.5, 0.0);
+        let green = Color::new(0.0, 1.0, 0.0);
+        let cyan = Color::new(0.0, 1.0, 1.0);
+        let blue = Color::new(0.0, 0.0, 1.0);
+        let purple = Color::new(1.0, 0.0, 1.0);
+        let white = Color::white();
+        let face =
+            |main, ul, ur, bl, br| UvPattern::AlignCheck(UvAlignCheck::new(main, ul, ur, bl, br));
+        let pattern = Pattern::CubeMap(Box::new(CubeMapPattern::new([
+            face(red, yellow, purple, green, white),
+            face(yellow, cyan, red, blue, brown),
+            face(brown, cyan, purple, red, yellow),
+            face(purple, brown, green, blue, white),
+            face(cyan, red, yellow, brown, green),
+            face(green, purple, cyan, white, blue),
+        ])));
+
+        // Corners shared by neighboring faces get matching colors.
+        let cases = [
+            (Point::new(1.0, 0.0, 0.0), red),
+            (Point::new(1.0, 0.9, 0.9), yellow),
+            (Point::new(-1.0, 0.9, -0.9), cyan),
+            (Point::new(-1.0, -0.9, 0.9), brown),
+            (Point::new(-0.9, 1.0, -0.9), cyan),
+            (Point::new(0.9, 1.0, 0.9), yellow),
+            (Point::new(-0.9, -1.0, -0.9), blue),
+            (Point::new(0.9, 0.9, 1.0), yellow),
+            (Point::new(-0.9, -0.9, 1.0), brown),
+            (Point::new(-0.9, 0.9, -1.0), cyan),
+            (Point::new(0.9, -0.9, -1.0), white),
+        ];
+        for (point, color) in cases {
+            assert_eq!(pattern.color_at(point), color);
+        }
+    }
+
+    #[test]
+    fn blended_patterns() {
+        let mut stripes = StripePattern::new(Color::white(), Color::new(0.0, 0.0, 1.0));
+        stripes.set_transform(Matrix::rotation_y(std::f64::consts::FRAC_PI_2));
+        let pattern = BlendPattern::new(
+            Pattern::Stripe(StripePattern::new(
+                Color::white(),
+                Color::new(1.0, 0.0, 0.0),
+            )),
+            Pattern::Stripe(stripes),
+            0.5,
+        );
+
+        assert_eq!(pattern.color_at(Point::new(0.5, 0.0, -0.5)), Color::white());
+        assert_eq!(
+            pattern.color_at(Point::new(1.5, 0.0, -0.5)),
+            Color::new(1.0, 0.5, 0.5)
+        );
+        assert_eq!(
+            pattern.color_at(Point::new(1.5, 0.0, 0.5)),
+            Color::new(0.5, 0.0, 0.5)
+        );
+    }
+
+    #[test]
+    fn patterns_in_checker_slots() {
+        let mut small = StripePattern::default();
+        small.set_transform(Matrix::scaling(Vector::new(0.25, 1.0, 1.0)));
+        let red = Color::new(1.0, 0.0, 0.0);
+        let mut pattern = Pattern::Mask(MaskPattern::checker(
+            Pattern::Stripe(small),
+            Pattern::Gradient(GradientPattern::new(red, Color::black())),
+        ));
+        pattern.set_transform(Matrix::scaling(Vector::new(2.0, 2.0, 2.0)));
+
+        let plane = Object::Plane(Plane::default());
+        let cases = [
+            (Point::new(0.1, 0.0, 0.1), Color::white()),
+            (Point::new(0.6, 0.0, 0.1), Color::black()),
+            (Point::new(2.5, 0.0, 0.1), red * 0.75),
+        ];
+        for (point, color) in cases {
+            assert_eq!(pattern.color_at_object(&plane, point), color);
+        }
+    }
+
+    #[test]
+    fn solid_and_unset_patterns() {
+        let red = Color::new(1.0, 0.0, 0.0);
+        let solid = Pattern::Solid(red);
+        assert_eq!(solid.color_at(Point::new(3.2, -1.0, 7.5)), red);
+        assert_eq!(Pattern::None.color_at(Point::default()), Color::black());
+
+        let pattern = MaskPattern::stripe(solid, Pattern::None);
+        assert_eq!(pattern.color_at(Point::new(0.5, 0.0, 0.0)), red);
+        assert_eq!(pattern.color_at(Point::new(1.5, 0.0, 0.0)), Color::black());
+    }
+
+    #[test]
+    fn custom_pattern_in_material() {
+        use crate::{Material, Sphere};
+
+        // Stands in for a pattern type defined downstream.
+        struct Dots {
+            transform: Matrix,
+        }
+
+        impl Transformable for Dots {
+            fn get_transform(&self) -> Matrix {
+                self.transform
+            }
+
+            fn set_transform(&mut self, transform: Matrix) {
+                self.transform = transform;
+            }
+        }
+
+        impl Patterned for Dots {
+            fn color_at(&self, point: Point) -> Color {
+                let cell = Point::new(point.x.round(), point.y.round(), point.z.round());
+                if (point - cell).magnitude() < 0.25 {
+                    Color::white()
+                } else {
+                    Color::black()
+                }
+            }
+        }
+
+        let dots = CustomPattern::new(Dots {
+            transform: Matrix::scaling(Vector::new(0.5, 0.5, 0.5)),
+        });
+        let pattern = Pattern::Custom(dots.clone());
+        assert_eq!(pattern, Pattern::Custom(dots));
+        assert_ne!(
+            pattern,
+            Pattern::Custom(CustomPattern::new(TestPattern::default()))
+        );
+
+        let material = Material {
+            pattern,
+            ..Material::default()
+        };
+        let object = Object::Sphere(Sphere::new(Matrix::default(), material.clone()));
+        assert_eq!(
+            material.surface_color(&object, Point::new(0.5, 0.0, 0.0)),
+            Color::white()
+        );
+        assert_eq!(
+            material.surface_color(&object, Point::new(0.25, 0.0, 0.0)),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn try_color_at() {
+        assert!(matches!(
+            Pattern::None.try_color_at(Point::default()),
+            Err(RaytracerError::UninitializedPattern)
+        ));
+        assert_eq!(
+            Pattern::Stripe(StripePattern::default())
+                .try_color_at(Point::default())
+                .unwrap(),
+            Color::white()
+        );
+    }
 }
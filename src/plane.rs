@@ -1,11 +1,53 @@
+use crate::shape::next_id;
 use crate::transformations::Transformable;
 use crate::utils::EPSILON;
-use crate::{vector, Intersection, Material, Matrix, Object, Point, Ray, Shape, Vector};
+use crate::uv;
+use crate::{
+    vector, BoundingBox, Intersection, Material, Matrix, Object, Point, Ray, Shape, Vector,
+};
 
-#[derive(Debug, Default, PartialEq, Clone, Copy)]
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone)]
 pub struct Plane {
     transform: Matrix,
     material: Material,
+    pub(crate) id: usize,
+}
+
+impl Plane {
+    #[must_use]
+    pub fn with_normal(normal: Vector, point: Point) -> Self {
+        let normal = normal.normalize();
+        let axis = vector::Y.cross(&normal);
+        let rotation = if axis.magnitude() > EPSILON {
+            Matrix::rotation_axis(axis, vector::Y.dot(&normal).clamp(-1.0, 1.0).acos())
+        } else if normal.y > 0.0 {
+            Matrix::default()
+        } else {
+            Matrix::rotation_x(PI)
+        };
+
+        let mut plane = Self::default();
+        plane.set_transform(Matrix::translation(point - Point::default()) * rotation);
+        plane
+    }
+}
+
+impl Default for Plane {
+    fn default() -> Self {
+        Self {
+            transform: Matrix::default(),
+            material: Material::default(),
+            id: next_id(),
+        }
+    }
+}
+
+impl PartialEq for Plane {
+    fn eq(&self, other: &Self) -> bool {
+        self.transform == other.transform && self.material == other.material
+    }
 }
 
 impl Transformable for Plane {
@@ -19,8 +61,12 @@ impl Transformable for Plane {
 }
 
 impl Shape for Plane {
+    fn id(&self) -> usize {
+        self.id
+    }
+
     fn get_material(&self) -> Material {
-        self.material
+        self.material.clone()
     }
 
     fn set_material(&mut self, material: Material) {
@@ -33,7 +79,7 @@ impl Shape for Plane {
         } else {
             vec![Intersection::new(
                 -ray.origin.y / ray.direction.y,
-                &Object::Plane(*self),
+                &Object::Plane(self.clone()),
             )]
         }
     }
@@ -41,6 +87,17 @@ impl Shape for Plane {
     fn local_normal_at(&self, _point: Point) -> Vector {
         vector::Y
     }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Point::new(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            Point::new(f64::INFINITY, 0.0, f64::INFINITY),
+        )
+    }
+
+    fn uv_at(&self, point: Point) -> Option<(f64, f64)> {
+        Some(uv::planar_map(point))
+    }
 }
 
 #[cfg(test)]
@@ -91,4 +148,18 @@ mod tests {
         assert_eq!(intersections[0].t, 1.0);
         assert_eq!(intersections[0].object, Object::Plane(p));
     }
+
+    #[test]
+    fn with_normal() {
+        let p = Object::Plane(Plane::with_normal(vector::X, Point::default()));
+        assert_eq!(p.normal_at(Point::new(0.0, 3.0, -2.0)), vector::X);
+
+        let r = Ray::new(Point::new(5.0, 1.0, 2.0), -vector::X);
+        let intersections = r.intersect(&p);
+        assert_eq!(intersections.len(), 1);
+        assert_eq!(r.position(intersections[0].t), Point::new(0.0, 1.0, 2.0));
+
+        let floor = Plane::with_normal(-vector::Y, Point::new(0.0, 3.0, 0.0));
+        assert_eq!(floor.normal_at(Point::new(1.0, 3.0, 1.0)), -vector::Y);
+    }
 }
@@ -1,6 +1,8 @@
-use crate::utils::equal;
+use crate::tuple::Tuple;
+use crate::utils::{equal_rel, equal_within, EPSILON};
 use crate::Vector;
 
+use std::fmt;
 use std::ops::{Add, Sub};
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -33,11 +35,71 @@ impl Point {
     pub fn new(x: f64, y: f64, z: f64) -> Self {
         Self { x, y, z }
     }
+
+    // Theta is measured from the +y axis, phi around it from +x towards +z.
+    #[must_use]
+    pub fn from_spherical(r: f64, theta: f64, phi: f64) -> Self {
+        let (sin_theta, cos_theta) = theta.sin_cos();
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        Self::new(
+            r * sin_theta * cos_phi,
+            r * cos_theta,
+            r * sin_theta * sin_phi,
+        )
+    }
+
+    #[must_use]
+    pub fn to_spherical(&self) -> (f64, f64, f64) {
+        let r = (self.x * self.x + self.y * self.y + self.z * self.z).sqrt();
+        if r == 0.0 {
+            return (0.0, 0.0, 0.0);
+        }
+
+        let (_, phi, _) = self.to_cylindrical();
+        (r, (self.y / r).clamp(-1.0, 1.0).acos(), phi)
+    }
+
+    #[must_use]
+    pub fn from_cylindrical(radius: f64, phi: f64, height: f64) -> Self {
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        Self::new(radius * cos_phi, height, radius * sin_phi)
+    }
+
+    #[must_use]
+    pub fn to_cylindrical(&self) -> (f64, f64, f64) {
+        let radius = self.x.hypot(self.z);
+        let phi = if radius == 0.0 {
+            0.0
+        } else {
+            self.z.atan2(self.x)
+        };
+        (radius, phi, self.y)
+    }
+
+    #[must_use]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        equal_within(self.x, other.x, epsilon)
+            && equal_within(self.y, other.y, epsilon)
+            && equal_within(self.z, other.z, epsilon)
+    }
+
+    #[must_use]
+    pub fn approx_eq_rel(&self, other: &Self, abs_tol: f64, rel_tol: f64) -> bool {
+        equal_rel(self.x, other.x, abs_tol, rel_tol)
+            && equal_rel(self.y, other.y, abs_tol, rel_tol)
+            && equal_rel(self.z, other.z, abs_tol, rel_tol)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
 }
 
 impl PartialEq for Point {
     fn eq(&self, other: &Self) -> bool {
-        equal(self.x, other.x) && equal(self.y, other.y) && equal(self.z, other.z)
+        self.approx_eq(other, EPSILON)
     }
 }
 
@@ -45,11 +107,7 @@ impl Add<Vector> for Point {
     type Output = Self;
 
     fn add(self, other: Vector) -> Self {
-        Self {
-            x: self.x + other.x,
-            y: self.y + other.y,
-            z: self.z + other.z,
-        }
+        (Tuple::from(self) + Tuple::from(other)).into()
     }
 }
 
@@ -57,11 +115,7 @@ impl Sub<Vector> for Point {
     type Output = Self;
 
     fn sub(self, other: Vector) -> Self {
-        Self {
-            x: self.x - other.x,
-            y: self.y - other.y,
-            z: self.z - other.z,
-        }
+        (Tuple::from(self) - Tuple::from(other)).into()
     }
 }
 
@@ -69,17 +123,55 @@ impl Sub for Point {
     type Output = Vector;
 
     fn sub(self, other: Self) -> Vector {
-        Vector {
-            x: self.x - other.x,
-            y: self.y - other.y,
-            z: self.z - other.z,
-        }
+        (Tuple::from(self) - Tuple::from(other)).into()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::equal;
+
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn display_point() {
+        assert_eq!(Point::new(1.0, -2.5, 0.25).to_string(), "(1, -2.5, 0.25)");
+    }
+
+    #[test]
+    fn spherical_coordinates() {
+        assert_eq!(Point::from_spherical(1.0, 0.0, FRAC_PI_2), UY);
+        assert_eq!(
+            Point::from_spherical(2.0, FRAC_PI_2, FRAC_PI_2),
+            Point::new(0.0, 0.0, 2.0)
+        );
+        assert_eq!(UY.to_spherical(), (1.0, 0.0, 0.0));
+        assert_eq!(Point::default().to_spherical(), (0.0, 0.0, 0.0));
+
+        for &(r, theta, phi) in &[(1.0, 0.3, 0.2), (2.5, 2.0, -1.5), (0.1, FRAC_PI_2, PI)] {
+            let (r2, theta2, phi2) = Point::from_spherical(r, theta, phi).to_spherical();
+            assert!(equal(r, r2) && equal(theta, theta2) && equal(phi, phi2));
+        }
+    }
+
+    #[test]
+    fn cylindrical_coordinates() {
+        assert_eq!(
+            Point::from_cylindrical(1.0, 0.0, 3.0),
+            Point::new(1.0, 3.0, 0.0)
+        );
+        assert_eq!(
+            Point::new(0.0, -2.0, 0.0).to_cylindrical(),
+            (0.0, 0.0, -2.0)
+        );
+
+        for &(radius, phi, height) in &[(1.0, 0.5, 0.0), (3.0, -2.5, 4.0), (0.5, PI, -1.0)] {
+            let (radius2, phi2, height2) =
+                Point::from_cylindrical(radius, phi, height).to_cylindrical();
+            assert!(equal(radius, radius2) && equal(phi, phi2) && equal(height, height2));
+        }
+    }
 
     #[test]
     fn new_point() {
@@ -109,4 +201,27 @@ mod tests {
         let v = Vector::new(5.0, 6.0, 7.0);
         assert_eq!(p - v, Point::new(-2.0, -4.0, -6.0));
     }
+
+    #[test]
+    fn point_approx_eq() {
+        let p1 = Point::new(1.0, 2.0, 3.0);
+        let p2 = Point::new(1.001, 2.0, 3.0);
+        assert!(p1.approx_eq(&p2, 0.01));
+        assert!(!p1.approx_eq(&p2, 0.0001));
+        assert_ne!(p1, p2);
+    }
+
+    #[test]
+    fn point_approx_eq_rel() {
+        let p1 = Point::new(1e6, -2e6, 3e6);
+        let p2 = Point::new(1e6 + 0.01, -2e6, 3e6 - 0.02);
+
+        assert_ne!(p1, p2);
+        assert!(p1.approx_eq_rel(&p2, EPSILON, 1e-7));
+        assert!(!Point::new(1.0, 0.0, 0.0).approx_eq_rel(
+            &Point::new(1.01, 0.0, 0.0),
+            EPSILON,
+            1e-7
+        ));
+    }
 }
@@ -1,15 +1,37 @@
-use crate::{Intersection, Matrix, Point, Shape, Vector};
+use crate::{Color, Intersection, Matrix, Object, Point, Shape, Vector};
 
 #[derive(Debug, Default, PartialEq)]
 pub struct Ray {
     pub origin: Point,
     pub direction: Vector,
+    pub width: f64,
+    pub throughput: Color,
 }
 
 impl Ray {
     #[must_use]
     pub fn new(origin: Point, direction: Vector) -> Self {
-        Self { origin, direction }
+        Self {
+            origin,
+            direction,
+            width: 0.0,
+            throughput: Color::white(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_width(self, width: f64) -> Self {
+        Self { width, ..self }
+    }
+
+    #[must_use]
+    pub fn with_throughput(self, throughput: Color) -> Self {
+        Self { throughput, ..self }
+    }
+
+    #[must_use]
+    pub fn footprint(&self, t: f64) -> f64 {
+        self.width * t
     }
 
     #[must_use]
@@ -22,6 +44,8 @@ impl Ray {
         Self {
             origin: transformation * self.origin,
             direction: transformation * self.direction,
+            width: self.width,
+            throughput: self.throughput,
         }
     }
 
@@ -30,6 +54,11 @@ impl Ray {
         let ray = self.transform(&shape.get_transform().inverse());
         shape.local_intersect(&ray)
     }
+
+    #[must_use]
+    pub fn intersect_object(&self, object: &Object) -> Vec<Intersection> {
+        self.intersect(object)
+    }
 }
 
 #[cfg(test)]
@@ -70,4 +99,27 @@ mod tests {
         let s = TestShape::default();
         let _ = s.local_intersect(&Ray::new(Point::default(), -vector::Z));
     }
+
+    #[test]
+    fn intersect_object() {
+        use crate::{Material, Plane, Sphere};
+
+        let r = Ray::new(Point::new(0.0, 2.0, -5.0), Vector::new(0.0, -0.25, 1.0));
+        let sphere = Sphere::new(
+            Matrix::translation(Vector::new(0.0, 1.0, 0.0)),
+            Material::default(),
+        );
+        let plane = Plane::default();
+
+        assert_eq!(
+            r.intersect_object(&Object::Sphere(sphere.clone())),
+            r.intersect(&sphere)
+        );
+        assert_eq!(r.intersect_object(&Object::Sphere(sphere)).len(), 2);
+        assert_eq!(
+            r.intersect_object(&Object::Plane(plane.clone())),
+            r.intersect(&plane)
+        );
+        assert_eq!(r.intersect_object(&Object::Plane(plane)).len(), 1);
+    }
 }
@@ -0,0 +1,446 @@
+use crate::pattern::{Pattern, UvPattern};
+use crate::transformations::Transformable;
+use crate::utils::EPSILON;
+use crate::world::{Background, Fog};
+use crate::{
+    AreaLight, Bump, Camera, Canvas, Coat, Color, Light, Material, Matrix, Object, Pbr, Point,
+    PointLight, Shape, Vector, World,
+};
+
+use std::hash::Hasher;
+
+pub trait SceneHash {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H);
+
+    #[must_use]
+    fn quantized_hash(&self) -> u64 {
+        let mut hasher = Fnv1a::default();
+        self.hash_quantized(&mut hasher);
+        hasher.finish()
+    }
+}
+
+// User code (custom patterns, distance functions) can't be hashed by value,
+// so it hashes by address: clones match, but only within one run.
+fn hash_identity<H: Hasher>(ptr: *const (), state: &mut H) {
+    state.write_usize(ptr as usize);
+}
+
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+impl SceneHash for f64 {
+    #[allow(clippy::cast_possible_truncation)]
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        state.write_i64((self / EPSILON).round() as i64);
+    }
+}
+
+impl SceneHash for bool {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        state.write_u8(u8::from(*self));
+    }
+}
+
+impl SceneHash for usize {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(*self);
+    }
+}
+
+impl<T: SceneHash> SceneHash for Option<T> {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Some(value) => {
+                state.write_u8(1);
+                value.hash_quantized(state);
+            }
+            None => state.write_u8(0),
+        }
+    }
+}
+
+impl<A: SceneHash, B: SceneHash> SceneHash for (A, B) {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        self.0.hash_quantized(state);
+        self.1.hash_quantized(state);
+    }
+}
+
+impl<T: SceneHash> SceneHash for [T] {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for item in self {
+            item.hash_quantized(state);
+        }
+    }
+}
+
+impl SceneHash for Color {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        (self.r, self.g).hash_quantized(state);
+        self.b.hash_quantized(state);
+    }
+}
+
+impl SceneHash for Point {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        (self.x, self.y).hash_quantized(state);
+        self.z.hash_quantized(state);
+    }
+}
+
+impl SceneHash for Vector {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        (self.x, self.y).hash_quantized(state);
+        self.z.hash_quantized(state);
+    }
+}
+
+impl SceneHash for Matrix {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        self.dimension.hash_quantized(state);
+        self.grid.concat().hash_quantized(state);
+    }
+}
+
+impl SceneHash for Pattern {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        let (tag, colors) = match self {
+            Pattern::None => (0, None),
+            Pattern::Solid(color) => (11, Some((*color, *color))),
+            Pattern::Stripe(p) => (1, Some((p.color1, p.color2))),
+            Pattern::Gradient(p) => (2, Some((p.color1, p.color2))),
+            Pattern::Ring(p) => (3, Some((p.color1, p.color2))),
+            Pattern::Checker(p) => (4, Some((p.color1, p.color2))),
+            Pattern::Test(_) => (6, None),
+            Pattern::Uv(p) => {
+                p.hash_quantized(state);
+                (5, None)
+            }
+            Pattern::CubeMap(p) => {
+                p.faces[..].hash_quantized(state);
+                (8, None)
+            }
+            Pattern::Blend(p) => {
+                p.a.hash_quantized(state);
+                p.b.hash_quantized(state);
+                p.factor.hash_quantized(state);
+                (9, None)
+            }
+            Pattern::Custom(p) => {
+                hash_identity(p.pattern_ptr(), state);
+                (12, None)
+            }
+            Pattern::Mask(p) => {
+                p.mask.hash_quantized(state);
+                p.a.hash_quantized(state);
+                p.b.hash_quantized(state);
+                (10, None)
+            }
+            Pattern::TextureMap(p) => {
+                p.uv_pattern.hash_quantized(state);
+                state.write_u8(p.mapping as u8);
+                (7, None)
+            }
+        };
+        state.write_u8(tag);
+        colors.hash_quantized(state);
+        self.get_transform().hash_quantized(state);
+    }
+}
+
+impl SceneHash for UvPattern {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        match self {
+            UvPattern::Checkers(p) => {
+                (p.width, p.height).hash_quantized(state);
+                (p.color1, p.color2).hash_quantized(state);
+            }
+            UvPattern::AlignCheck(p) => {
+                (p.main, p.upper_left).hash_quantized(state);
+                (p.upper_right, p.bottom_left).hash_quantized(state);
+                p.bottom_right.hash_quantized(state);
+            }
+            UvPattern::Image(p) => {
+                p.canvas().hash_quantized(state);
+                p.linearize.hash_quantized(state);
+            }
+        }
+    }
+}
+
+impl SceneHash for Material {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        self.color.hash_quantized(state);
+        self.pattern.hash_quantized(state);
+        [
+            self.ambient,
+            self.diffuse,
+            self.specular,
+            self.shininess,
+            self.transparency,
+            self.refractive_index,
+            self.reflective,
+            self.roughness,
+        ]
+        .hash_quantized(state);
+        self.normal_tint.hash_quantized(state);
+        self.smooth_normals.hash_quantized(state);
+        self.material_transform.hash_quantized(state);
+        self.specular_color.hash_quantized(state);
+        self.emissive.hash_quantized(state);
+        self.coat.hash_quantized(state);
+        self.pbr.hash_quantized(state);
+        self.bump.hash_quantized(state);
+    }
+}
+
+impl SceneHash for Bump {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        self.pattern.hash_quantized(state);
+        self.strength.hash_quantized(state);
+    }
+}
+
+impl SceneHash for Pbr {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        [self.metallic, self.roughness].hash_quantized(state);
+    }
+}
+
+impl SceneHash for Coat {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        [self.reflective, self.refractive_index, self.shininess].hash_quantized(state);
+    }
+}
+
+impl SceneHash for Object {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Object::Sphere(_) => state.write_u8(0),
+            Object::Plane(_) => state.write_u8(1),
+            Object::Cube(_) => state.write_u8(4),
+            Object::Cylinder(cylinder) => {
+                state.write_u8(5);
+                (cylinder.minimum, cylinder.maximum).hash_quantized(state);
+                cylinder.closed.hash_quantized(state);
+            }
+            Object::Cone(cone) => {
+                state.write_u8(6);
+                (cone.minimum, cone.maximum).hash_quantized(state);
+                cone.closed.hash_quantized(state);
+            }
+            Object::Disc(disc) => {
+                state.write_u8(10);
+                disc.inner_radius.hash_quantized(state);
+            }
+            Object::SmoothTriangle(triangle) => {
+                state.write_u8(7);
+                [triangle.p1, triangle.p2, triangle.p3].hash_quantized(state);
+                [triangle.n1, triangle.n2, triangle.n3].hash_quantized(state);
+            }
+            Object::Clipped(clipped) => {
+                state.write_u8(2);
+                clipped.inner.hash_quantized(state);
+                clipped.planes.hash_quantized(state);
+            }
+            Object::Instances(instances) => {
+                state.write_u8(3);
+                instances.base().hash_quantized(state);
+                instances.transforms().hash_quantized(state);
+            }
+            Object::Csg(csg) => {
+                state.write_u8(8);
+                state.write_u8(csg.operation as u8);
+                csg.left.hash_quantized(state);
+                csg.right.hash_quantized(state);
+            }
+            Object::Mesh(mesh) => {
+                state.write_u8(9);
+                mesh.vertices().hash_quantized(state);
+                mesh.normals().unwrap_or_default().hash_quantized(state);
+                for face in mesh.faces() {
+                    face[..].hash_quantized(state);
+                }
+            }
+            Object::Sdf(sdf) => {
+                state.write_u8(11);
+                hash_identity(sdf.distance_ptr(), state);
+                let bounds = sdf.bounds();
+                (bounds.min, bounds.max).hash_quantized(state);
+                sdf.max_steps.hash_quantized(state);
+            }
+        }
+        self.get_transform().hash_quantized(state);
+        self.get_material().hash_quantized(state);
+    }
+}
+
+impl SceneHash for PointLight {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        self.position.hash_quantized(state);
+        self.intensity.hash_quantized(state);
+    }
+}
+
+impl SceneHash for AreaLight {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        (self.corner, self.intensity).hash_quantized(state);
+        (self.uvec, self.vvec).hash_quantized(state);
+        (self.usteps, self.vsteps).hash_quantized(state);
+        state.write_u64(self.seed);
+        self.sky_weighted.hash_quantized(state);
+    }
+}
+
+impl SceneHash for Light {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Light::Point(light) => {
+                state.write_u8(0);
+                light.hash_quantized(state);
+            }
+            Light::Area(light) => {
+                state.write_u8(1);
+                light.hash_quantized(state);
+            }
+        }
+    }
+}
+
+impl SceneHash for Fog {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        self.color.hash_quantized(state);
+        self.density.hash_quantized(state);
+    }
+}
+
+impl SceneHash for Canvas {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        (self.width(), self.height()).hash_quantized(state);
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                self.pixel_at(x, y).hash_quantized(state);
+            }
+        }
+    }
+}
+
+impl SceneHash for Background {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Background::Solid(color) => {
+                state.write_u8(0);
+                color.hash_quantized(state);
+            }
+            Background::Gradient { top, bottom } => {
+                state.write_u8(1);
+                (*top, *bottom).hash_quantized(state);
+            }
+            Background::CubeMap(faces) => {
+                state.write_u8(2);
+                faces[..].hash_quantized(state);
+            }
+        }
+    }
+}
+
+impl SceneHash for World {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        self.objects.hash_quantized(state);
+        self.lights[..].hash_quantized(state);
+        self.fog.hash_quantized(state);
+        self.background.hash_quantized(state);
+        self.reflection_background.hash_quantized(state);
+        self.max_recursion_depth.hash_quantized(state);
+    }
+}
+
+impl SceneHash for Camera {
+    fn hash_quantized<H: Hasher>(&self, state: &mut H) {
+        (self.h_size, self.v_size).hash_quantized(state);
+        self.field_of_view.hash_quantized(state);
+        self.transform.hash_quantized(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::{CustomPattern, TestPattern};
+    use crate::world::test_world::test_world;
+    use crate::{BoundingBox, SdfShape};
+
+    use std::f64::consts::PI;
+
+    #[test]
+    fn equal_worlds_hash_equally() {
+        let a = test_world();
+        let mut b = test_world();
+
+        assert_eq!(a.scene_hash(), b.scene_hash());
+
+        b.lights[0] = PointLight::new(
+            b.lights[0].position() + Vector::new(EPSILON / 10.0, 0.0, 0.0),
+            b.lights[0].intensity(),
+        )
+        .into();
+        assert_eq!(a.scene_hash(), b.scene_hash());
+
+        b.update_object(0, |object| {
+            let mut material = object.get_material();
+            material.color = Color::new(0.8, 1.0, 0.7);
+            object.set_material(material);
+        });
+        assert_ne!(a.scene_hash(), b.scene_hash());
+    }
+
+    #[test]
+    fn opaque_parts_hash_by_identity() {
+        let custom = Pattern::Custom(CustomPattern::new(TestPattern::default()));
+        let other = Pattern::Custom(CustomPattern::new(TestPattern::default()));
+        assert_eq!(custom.quantized_hash(), custom.clone().quantized_hash());
+        assert_ne!(custom.quantized_hash(), other.quantized_hash());
+
+        let sphere = |point: Point| (point - Point::default()).magnitude() - 1.0;
+        let bounds = BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0));
+        let sdf = Object::Sdf(SdfShape::new(sphere, bounds));
+        let other = Object::Sdf(SdfShape::new(sphere, bounds));
+        assert_eq!(sdf.quantized_hash(), sdf.clone().quantized_hash());
+        assert_ne!(sdf.quantized_hash(), other.quantized_hash());
+    }
+
+    #[test]
+    fn camera_config_hash() {
+        let a = Camera::new(160, 120, PI / 3.0);
+        let mut b = Camera::new(160, 120, PI / 3.0);
+
+        assert_eq!(a.config_hash(), b.config_hash());
+
+        b.front_view(Point::default(), 5.0);
+        assert_ne!(a.config_hash(), b.config_hash());
+        assert_ne!(
+            a.config_hash(),
+            Camera::new(160, 121, PI / 3.0).config_hash()
+        );
+    }
+}
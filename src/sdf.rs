@@ -0,0 +1,195 @@
+use crate::shape::next_id;
+use crate::transformations::Transformable;
+use crate::utils::EPSILON;
+use crate::{BoundingBox, Intersection, Material, Matrix, Object, Point, Ray, Shape, Vector};
+use std::fmt;
+use std::sync::Arc;
+
+pub type DistanceFn = dyn Fn(Point) -> f64 + Send + Sync;
+
+// Implicit surface given by a signed distance function, negative inside.
+// Rays are sphere traced only within `bounds`, which must enclose the surface.
+#[derive(Clone)]
+pub struct SdfShape {
+    transform: Matrix,
+    material: Material,
+    distance: Arc<DistanceFn>,
+    bounds: BoundingBox,
+    pub max_steps: usize,
+    pub(crate) id: usize,
+}
+
+impl SdfShape {
+    #[must_use]
+    pub fn new(
+        distance: impl Fn(Point) -> f64 + Send + Sync + 'static,
+        bounds: BoundingBox,
+    ) -> Self {
+        Self {
+            transform: Matrix::default(),
+            material: Material::default(),
+            distance: Arc::new(distance),
+            bounds,
+            max_steps: 256,
+            id: next_id(),
+        }
+    }
+
+    #[must_use]
+    pub fn distance(&self, point: Point) -> f64 {
+        (self.distance)(point)
+    }
+
+    pub(crate) fn distance_ptr(&self) -> *const () {
+        Arc::as_ptr(&self.distance).cast()
+    }
+}
+
+impl Default for SdfShape {
+    fn default() -> Self {
+        Self::new(
+            |point| (point - Point::default()).magnitude() - 1.0,
+            BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0)),
+        )
+    }
+}
+
+impl fmt::Debug for SdfShape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SdfShape")
+            .field("transform", &self.transform)
+            .field("material", &self.material)
+            .field("bounds", &self.bounds)
+            .field("max_steps", &self.max_steps)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for SdfShape {
+    fn eq(&self, other: &Self) -> bool {
+        self.transform == other.transform
+            && self.material == other.material
+            && Arc::ptr_eq(&self.distance, &other.distance)
+            && self.bounds == other.bounds
+            && self.max_steps == other.max_steps
+    }
+}
+
+impl Transformable for SdfShape {
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Shape for SdfShape {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn get_material(&self) -> Material {
+        self.material.clone()
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    // Marches the whole span inside the bounds, so hits behind the origin
+    // are reported too. After each hit it steps off the surface and goes on.
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection> {
+        let Some((t_min, t_max)) = self.bounds.ray_interval(ray) else {
+            return Vec::new();
+        };
+
+        let scale = ray.direction.magnitude();
+        let mut xs = Vec::new();
+        let (mut t, mut on_surface) = (t_min, false);
+        for _ in 0..self.max_steps {
+            if t > t_max {
+                break;
+            }
+
+            let distance = self.distance(ray.position(t)).abs();
+            if distance < EPSILON {
+                if !on_surface {
+                    xs.push(Intersection::new(t, &Object::Sdf(self.clone())));
+                    on_surface = true;
+                }
+                t += EPSILON / scale;
+            } else {
+                on_surface = false;
+                t += distance / scale;
+            }
+        }
+        xs
+    }
+
+    // Central differences of the distance field.
+    fn local_normal_at(&self, point: Point) -> Vector {
+        let gradient =
+            |offset: Vector| self.distance(point + offset) - self.distance(point - offset);
+        Vector::new(
+            gradient(Vector::new(EPSILON, 0.0, 0.0)),
+            gradient(Vector::new(0.0, EPSILON, 0.0)),
+            gradient(Vector::new(0.0, 0.0, EPSILON)),
+        )
+        .normalize()
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::equal_within;
+    use crate::vector;
+
+    #[test]
+    fn sphere_traced_sphere() {
+        let sdf = SdfShape::default();
+        let xs = sdf.local_intersect(&Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z));
+
+        assert_eq!(xs.len(), 2);
+        assert!(equal_within(xs[0].t, 4.0, 1e-3));
+        assert!(equal_within(xs[1].t, 6.0, 1e-3));
+        assert!(xs[0].object.same_object(&Object::Sdf(sdf.clone())));
+
+        let miss = Ray::new(Point::new(0.0, 1.5, -5.0), vector::Z);
+        assert!(sdf.local_intersect(&miss).is_empty());
+
+        let normal = sdf.local_normal_at(Point::new(0.0, 0.0, -1.0));
+        assert_eq!(normal, -vector::Z);
+    }
+
+    #[test]
+    fn transformed_torus() {
+        let torus = SdfShape::new(
+            |p| {
+                let ring = (p.x * p.x + p.z * p.z).sqrt() - 1.0;
+                (ring * ring + p.y * p.y).sqrt() - 0.25
+            },
+            BoundingBox::new(
+                Point::new(-1.25, -0.25, -1.25),
+                Point::new(1.25, 0.25, 1.25),
+            ),
+        );
+        let mut object = Object::Sdf(torus);
+        object.set_transform(Matrix::scaling(Vector::new(2.0, 2.0, 2.0)));
+
+        // Straight down through the tube, and through the empty middle.
+        let xs = Ray::new(Point::new(2.0, 5.0, 0.0), -vector::Y).intersect(&object);
+        assert_eq!(xs.len(), 2);
+        assert!(equal_within(xs[0].t, 4.5, 1e-3));
+        assert_eq!(object.normal_at(Point::new(2.0, 0.5, 0.0)), vector::Y);
+        assert!(Ray::new(Point::new(0.0, 5.0, 0.0), -vector::Y)
+            .intersect(&object)
+            .is_empty());
+    }
+}
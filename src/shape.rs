@@ -1,30 +1,130 @@
+use crate::error::RaytracerError;
 use crate::transformations::Transformable;
-use crate::{Intersection, Material, Matrix, Plane, Point, Ray, Sphere, Vector};
+use crate::utils::EPSILON;
+use crate::{
+    BoundingBox, ClippedShape, Cone, Csg, Cube, Cylinder, Disc, Instances, Intersection, Material,
+    Matrix, Mesh, Plane, Point, Ray, SdfShape, SmoothTriangle, Sphere, Vector,
+};
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum Object {
     Sphere(Sphere),
     Plane(Plane),
+    Cube(Cube),
+    Cylinder(Cylinder),
+    Cone(Cone),
+    Disc(Disc),
+    SmoothTriangle(SmoothTriangle),
+    Clipped(ClippedShape),
+    Instances(Instances),
+    Csg(Csg),
+    Mesh(Mesh),
+    Sdf(SdfShape),
+}
+
+pub(crate) fn next_id() -> usize {
+    next_ids(1)
+}
+
+// Reserves `count` consecutive ids and returns the first.
+pub(crate) fn next_ids(count: usize) -> usize {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    NEXT_ID.fetch_add(count, Ordering::Relaxed)
 }
 
 pub trait Shape: Default + Transformable {
+    fn id(&self) -> usize;
+
     #[must_use]
     fn get_material(&self) -> Material;
     fn set_material(&mut self, material: Material);
 
     fn local_normal_at(&self, point: Point) -> Vector;
 
+    fn local_face_normal_at(&self, point: Point) -> Vector {
+        self.local_normal_at(point)
+    }
+
+    // Shapes that interpolate normals from the hit's (u, v) override this.
+    fn local_normal_at_uv(&self, point: Point, _u: f64, _v: f64) -> Vector {
+        self.local_normal_at(point)
+    }
+
+    // Composite shapes hand out hits whose objects already carry every
+    // enclosing transform, so the object's own transform spans the whole
+    // parent chain.
+    #[must_use]
+    fn world_to_object(&self, point: Point) -> Point {
+        self.get_transform().inverse() * point
+    }
+
+    #[must_use]
+    fn normal_to_world(&self, normal: Vector) -> Vector {
+        self.get_transform()
+            .inverse_transpose()
+            .transform_normal(normal)
+    }
+
     #[must_use]
     fn normal_at(&self, point: Point) -> Vector {
-        let object_point = self.get_transform().inverse() * point;
-        let object_normal = self.local_normal_at(object_point);
-        let world_normal = self.get_transform().inverse().transpose() * object_normal;
-        world_normal.normalize()
+        self.normal_at_uv(point, None)
+    }
+
+    #[must_use]
+    fn normal_at_uv(&self, point: Point, uv: Option<(f64, f64)>) -> Vector {
+        let object_point = self.world_to_object(point);
+        let object_normal = match uv {
+            _ if !self.get_material().smooth_normals => self.local_face_normal_at(object_point),
+            Some((u, v)) => self.local_normal_at_uv(object_point, u, v),
+            None => self.local_normal_at(object_point),
+        };
+        let normal = self.normal_to_world(object_normal);
+        // Degenerate local normals (e.g. a cone's apex) have no length to keep.
+        debug_assert!(
+            object_normal.magnitude() < EPSILON || (normal.magnitude() - 1.0).abs() < EPSILON,
+            "normal is not unit length"
+        );
+        normal
+    }
+
+    fn normal_at_checked(&self, point: Point) -> Result<Vector, RaytracerError> {
+        let inverse = self.get_transform().try_inverse()?;
+        let object_point = inverse * point;
+        let object_normal = if self.get_material().smooth_normals {
+            self.local_normal_at(object_point)
+        } else {
+            self.local_face_normal_at(object_point)
+        };
+
+        let normal = inverse.transpose().transform_normal(object_normal);
+        if (normal.magnitude() - 1.0).abs() < EPSILON {
+            Ok(normal)
+        } else {
+            Err(RaytracerError::DegenerateNormal)
+        }
+    }
+
+    #[must_use]
+    fn face_normal_at(&self, point: Point) -> Vector {
+        self.normal_to_world(self.local_face_normal_at(self.world_to_object(point)))
     }
 
     #[must_use]
     fn local_intersect(&self, ray: &Ray) -> Vec<Intersection>;
 
+    fn bounds(&self) -> BoundingBox;
+
+    fn uv_at(&self, _point: Point) -> Option<(f64, f64)> {
+        None
+    }
+
+    #[must_use]
+    fn parent_space_bounds(&self) -> BoundingBox {
+        self.bounds().transform(self.get_transform())
+    }
+
     #[must_use]
     fn new(transform: Matrix, material: Material) -> Self {
         let mut shape = Self::default();
@@ -35,6 +135,62 @@ pub trait Shape: Default + Transformable {
     }
 }
 
+impl Object {
+    #[must_use]
+    pub fn same_object(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    // Ids taken by this object and everything nested inside it.
+    pub(crate) fn id_count(&self) -> usize {
+        match self {
+            Object::Csg(csg) => 1 + csg.left.id_count() + csg.right.id_count(),
+            Object::Clipped(clipped) => 1 + clipped.inner.id_count(),
+            Object::Instances(instances) => instances.id_count(),
+            _ => 1,
+        }
+    }
+
+    // Numbers this object and its nested objects consecutively from `first`.
+    pub(crate) fn assign_ids(&mut self, first: usize) {
+        match self {
+            Object::Sphere(o) => o.id = first,
+            Object::Plane(o) => o.id = first,
+            Object::Cube(o) => o.id = first,
+            Object::Cylinder(o) => o.id = first,
+            Object::Cone(o) => o.id = first,
+            Object::Disc(o) => o.id = first,
+            Object::SmoothTriangle(o) => o.id = first,
+            Object::Mesh(o) => o.id = first,
+            Object::Sdf(o) => o.id = first,
+            Object::Csg(o) => {
+                o.id = first;
+                o.left.assign_ids(first + 1);
+                o.right.assign_ids(first + 1 + o.left.id_count());
+            }
+            Object::Clipped(o) => {
+                o.id = first;
+                o.inner.assign_ids(first + 1);
+            }
+            Object::Instances(o) => o.assign_ids(first),
+        }
+    }
+
+    // Whether `other` is this object or one nested inside it.
+    #[must_use]
+    pub fn includes(&self, other: &Self) -> bool {
+        self.includes_id(other.id())
+    }
+
+    pub(crate) fn includes_id(&self, id: usize) -> bool {
+        match self {
+            Object::Csg(csg) => csg.left.includes_id(id) || csg.right.includes_id(id),
+            Object::Instances(instances) => instances.includes_id(id),
+            _ => self.id() == id,
+        }
+    }
+}
+
 impl Default for Object {
     fn default() -> Self {
         Self::Sphere(Sphere::default())
@@ -43,9 +199,19 @@ impl Default for Object {
 
 impl Transformable for Object {
     fn get_transform(&self) -> Matrix {
-        match *self {
+        match self {
             Object::Sphere(o) => o.get_transform(),
             Object::Plane(o) => o.get_transform(),
+            Object::Cube(o) => o.get_transform(),
+            Object::Cylinder(o) => o.get_transform(),
+            Object::Cone(o) => o.get_transform(),
+            Object::Disc(o) => o.get_transform(),
+            Object::SmoothTriangle(o) => o.get_transform(),
+            Object::Clipped(o) => o.get_transform(),
+            Object::Instances(o) => o.get_transform(),
+            Object::Csg(o) => o.get_transform(),
+            Object::Mesh(o) => o.get_transform(),
+            Object::Sdf(o) => o.get_transform(),
         }
     }
 
@@ -53,15 +219,52 @@ impl Transformable for Object {
         match self {
             Object::Sphere(o) => o.set_transform(transform),
             Object::Plane(o) => o.set_transform(transform),
+            Object::Cube(o) => o.set_transform(transform),
+            Object::Cylinder(o) => o.set_transform(transform),
+            Object::Cone(o) => o.set_transform(transform),
+            Object::Disc(o) => o.set_transform(transform),
+            Object::SmoothTriangle(o) => o.set_transform(transform),
+            Object::Clipped(o) => o.set_transform(transform),
+            Object::Instances(o) => o.set_transform(transform),
+            Object::Csg(o) => o.set_transform(transform),
+            Object::Mesh(o) => o.set_transform(transform),
+            Object::Sdf(o) => o.set_transform(transform),
         }
     }
 }
 
 impl Shape for Object {
+    fn id(&self) -> usize {
+        match self {
+            Object::Sphere(o) => o.id(),
+            Object::Plane(o) => o.id(),
+            Object::Cube(o) => o.id(),
+            Object::Cylinder(o) => o.id(),
+            Object::Cone(o) => o.id(),
+            Object::Disc(o) => o.id(),
+            Object::SmoothTriangle(o) => o.id(),
+            Object::Clipped(o) => o.id(),
+            Object::Instances(o) => o.id(),
+            Object::Csg(o) => o.id(),
+            Object::Mesh(o) => o.id(),
+            Object::Sdf(o) => o.id(),
+        }
+    }
+
     fn get_material(&self) -> Material {
-        match *self {
+        match self {
             Object::Sphere(o) => o.get_material(),
             Object::Plane(o) => o.get_material(),
+            Object::Cube(o) => o.get_material(),
+            Object::Cylinder(o) => o.get_material(),
+            Object::Cone(o) => o.get_material(),
+            Object::Disc(o) => o.get_material(),
+            Object::SmoothTriangle(o) => o.get_material(),
+            Object::Clipped(o) => o.get_material(),
+            Object::Instances(o) => o.get_material(),
+            Object::Csg(o) => o.get_material(),
+            Object::Mesh(o) => o.get_material(),
+            Object::Sdf(o) => o.get_material(),
         }
     }
 
@@ -69,6 +272,16 @@ impl Shape for Object {
         match self {
             Object::Sphere(o) => o.set_material(material),
             Object::Plane(o) => o.set_material(material),
+            Object::Cube(o) => o.set_material(material),
+            Object::Cylinder(o) => o.set_material(material),
+            Object::Cone(o) => o.set_material(material),
+            Object::Disc(o) => o.set_material(material),
+            Object::SmoothTriangle(o) => o.set_material(material),
+            Object::Clipped(o) => o.set_material(material),
+            Object::Instances(o) => o.set_material(material),
+            Object::Csg(o) => o.set_material(material),
+            Object::Mesh(o) => o.set_material(material),
+            Object::Sdf(o) => o.set_material(material),
         }
     }
 
@@ -76,6 +289,16 @@ impl Shape for Object {
         match self {
             Object::Sphere(o) => o.local_intersect(ray),
             Object::Plane(o) => o.local_intersect(ray),
+            Object::Cube(o) => o.local_intersect(ray),
+            Object::Cylinder(o) => o.local_intersect(ray),
+            Object::Cone(o) => o.local_intersect(ray),
+            Object::Disc(o) => o.local_intersect(ray),
+            Object::SmoothTriangle(o) => o.local_intersect(ray),
+            Object::Clipped(o) => o.local_intersect(ray),
+            Object::Instances(o) => o.local_intersect(ray),
+            Object::Csg(o) => o.local_intersect(ray),
+            Object::Mesh(o) => o.local_intersect(ray),
+            Object::Sdf(o) => o.local_intersect(ray),
         }
     }
 
@@ -83,6 +306,84 @@ impl Shape for Object {
         match self {
             Object::Sphere(o) => o.local_normal_at(point),
             Object::Plane(o) => o.local_normal_at(point),
+            Object::Cube(o) => o.local_normal_at(point),
+            Object::Cylinder(o) => o.local_normal_at(point),
+            Object::Cone(o) => o.local_normal_at(point),
+            Object::Disc(o) => o.local_normal_at(point),
+            Object::SmoothTriangle(o) => o.local_normal_at(point),
+            Object::Clipped(o) => o.local_normal_at(point),
+            Object::Instances(o) => o.local_normal_at(point),
+            Object::Csg(o) => o.local_normal_at(point),
+            Object::Mesh(o) => o.local_normal_at(point),
+            Object::Sdf(o) => o.local_normal_at(point),
+        }
+    }
+
+    fn local_normal_at_uv(&self, point: Point, u: f64, v: f64) -> Vector {
+        match self {
+            Object::Sphere(o) => o.local_normal_at_uv(point, u, v),
+            Object::Plane(o) => o.local_normal_at_uv(point, u, v),
+            Object::Cube(o) => o.local_normal_at_uv(point, u, v),
+            Object::Cylinder(o) => o.local_normal_at_uv(point, u, v),
+            Object::Cone(o) => o.local_normal_at_uv(point, u, v),
+            Object::Disc(o) => o.local_normal_at_uv(point, u, v),
+            Object::SmoothTriangle(o) => o.local_normal_at_uv(point, u, v),
+            Object::Clipped(o) => o.local_normal_at_uv(point, u, v),
+            Object::Instances(o) => o.local_normal_at_uv(point, u, v),
+            Object::Csg(o) => o.local_normal_at_uv(point, u, v),
+            Object::Mesh(o) => o.local_normal_at_uv(point, u, v),
+            Object::Sdf(o) => o.local_normal_at_uv(point, u, v),
+        }
+    }
+
+    fn local_face_normal_at(&self, point: Point) -> Vector {
+        match self {
+            Object::Sphere(o) => o.local_face_normal_at(point),
+            Object::Plane(o) => o.local_face_normal_at(point),
+            Object::Cube(o) => o.local_face_normal_at(point),
+            Object::Cylinder(o) => o.local_face_normal_at(point),
+            Object::Cone(o) => o.local_face_normal_at(point),
+            Object::Disc(o) => o.local_face_normal_at(point),
+            Object::SmoothTriangle(o) => o.local_face_normal_at(point),
+            Object::Clipped(o) => o.local_face_normal_at(point),
+            Object::Instances(o) => o.local_face_normal_at(point),
+            Object::Csg(o) => o.local_face_normal_at(point),
+            Object::Mesh(o) => o.local_face_normal_at(point),
+            Object::Sdf(o) => o.local_face_normal_at(point),
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        match self {
+            Object::Sphere(o) => o.bounds(),
+            Object::Plane(o) => o.bounds(),
+            Object::Cube(o) => o.bounds(),
+            Object::Cylinder(o) => o.bounds(),
+            Object::Cone(o) => o.bounds(),
+            Object::Disc(o) => o.bounds(),
+            Object::SmoothTriangle(o) => o.bounds(),
+            Object::Clipped(o) => o.bounds(),
+            Object::Instances(o) => o.bounds(),
+            Object::Csg(o) => o.bounds(),
+            Object::Mesh(o) => o.bounds(),
+            Object::Sdf(o) => o.bounds(),
+        }
+    }
+
+    fn uv_at(&self, point: Point) -> Option<(f64, f64)> {
+        match self {
+            Object::Sphere(o) => o.uv_at(point),
+            Object::Plane(o) => o.uv_at(point),
+            Object::Cube(o) => o.uv_at(point),
+            Object::Cylinder(o) => o.uv_at(point),
+            Object::Cone(o) => o.uv_at(point),
+            Object::Disc(o) => o.uv_at(point),
+            Object::SmoothTriangle(o) => o.uv_at(point),
+            Object::Clipped(o) => o.uv_at(point),
+            Object::Instances(o) => o.uv_at(point),
+            Object::Csg(o) => o.uv_at(point),
+            Object::Mesh(o) => o.uv_at(point),
+            Object::Sdf(o) => o.uv_at(point),
         }
     }
 }
@@ -91,11 +392,23 @@ impl Shape for Object {
 pub(crate) mod testshape {
     use super::*;
 
-    #[derive(Debug, Default)]
+    #[derive(Debug)]
     pub struct TestShape {
         pub transform: Matrix,
         pub material: Material,
         pub test_ray: Ray,
+        id: usize,
+    }
+
+    impl Default for TestShape {
+        fn default() -> Self {
+            Self {
+                transform: Matrix::default(),
+                material: Material::default(),
+                test_ray: Ray::default(),
+                id: next_id(),
+            }
+        }
     }
 
     impl Transformable for TestShape {
@@ -109,8 +422,12 @@ pub(crate) mod testshape {
     }
 
     impl Shape for TestShape {
+        fn id(&self) -> usize {
+            self.id
+        }
+
         fn get_material(&self) -> Material {
-            self.material
+            self.material.clone()
         }
 
         fn set_material(&mut self, material: Material) {
@@ -125,6 +442,14 @@ pub(crate) mod testshape {
         fn local_normal_at(&self, point: Point) -> Vector {
             point - Point::default()
         }
+
+        fn local_face_normal_at(&self, _point: Point) -> Vector {
+            Vector::new(0.0, 0.0, -1.0)
+        }
+
+        fn bounds(&self) -> BoundingBox {
+            BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0))
+        }
     }
 }
 
@@ -133,7 +458,7 @@ mod tests {
     use super::testshape::TestShape;
     use super::*;
     use crate::utils::equal;
-    use crate::{Color, Pattern};
+    use crate::{vector, Color, CsgOperation, Pattern};
     use std::f64::consts::PI;
 
     #[test]
@@ -169,6 +494,27 @@ mod tests {
         assert_eq!(s.get_material().diffuse, 0.5);
     }
 
+    #[test]
+    fn checked_normals() {
+        let flat = TestShape::new(
+            Matrix::scaling(Vector::new(1.0, 0.0, 1.0)),
+            Material::default(),
+        );
+        assert!(matches!(
+            flat.normal_at_checked(Point::new(0.0, 1.0, 0.0)),
+            Err(RaytracerError::SingularMatrix)
+        ));
+
+        let s = Sphere::new(
+            Matrix::rotation_z(PI / 5.0) * Matrix::scaling(Vector::new(1.0, 0.5, 3.0)),
+            Material::default(),
+        );
+        let point = Point::new(0.0, 2_f64.sqrt() / 2.0, -(2_f64.sqrt()) / 2.0);
+        let normal = s.normal_at_checked(point).unwrap();
+        assert!(equal(normal.magnitude(), 1.0));
+        assert_eq!(normal, s.normal_at(point));
+    }
+
     #[test]
     fn translated_normals() {
         let s = TestShape::new(
@@ -209,4 +555,88 @@ mod tests {
             1.0,
         ));
     }
+
+    #[test]
+    fn flat_normals_ignore_hit_point() {
+        let mut s = TestShape::default();
+        let p1 = Point::new(0.0, 1.0, 0.0);
+        let p2 = Point::new(1.0, 0.0, 0.0);
+
+        assert_eq!(s.normal_at(p1), Vector::new(0.0, 1.0, 0.0));
+        assert_eq!(s.normal_at(p2), Vector::new(1.0, 0.0, 0.0));
+
+        s.material.smooth_normals = false;
+        assert_eq!(s.normal_at(p1), Vector::new(0.0, 0.0, -1.0));
+        assert_eq!(s.normal_at(p2), Vector::new(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn normal_at_cone_apex() {
+        let cone = Object::Cone(Cone::default());
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
+        let xs = ray.intersect(&cone);
+
+        assert!(!xs.is_empty());
+        let _ = xs[0].object.normal_at(ray.position(xs[0].t));
+    }
+
+    #[test]
+    fn face_normal_ignores_smoothing() {
+        let s = TestShape::new(
+            Matrix::translation(Vector::new(0.0, 1.0, 0.0)),
+            Material::default(),
+        );
+
+        assert_eq!(
+            s.face_normal_at(Point::new(1.0, 1.0, 0.0)),
+            Vector::new(0.0, 0.0, -1.0)
+        );
+        assert_eq!(
+            s.normal_at(Point::new(1.0, 1.0, 0.0)),
+            Vector::new(1.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn identical_objects_have_distinct_ids() {
+        let a = Object::Sphere(Sphere::default());
+        let b = Object::Sphere(Sphere::default());
+
+        assert_eq!(a, b);
+        assert_ne!(a.id(), b.id());
+        assert!(!a.same_object(&b));
+        assert!(a.same_object(&a.clone()));
+    }
+
+    #[test]
+    fn nested_space_conversions() {
+        let sphere = Object::Sphere(Sphere::new(
+            Matrix::translation(Vector::new(5.0, 0.0, 0.0)),
+            Material::default(),
+        ));
+        let far = || {
+            Object::Sphere(Sphere::new(
+                Matrix::translation(Vector::new(0.0, 100.0, 0.0)),
+                Material::default(),
+            ))
+        };
+        let mut inner = Csg::new(CsgOperation::Union, sphere.clone(), far());
+        inner.set_transform(Matrix::scaling(Vector::new(2.0, 2.0, 2.0)));
+        let mut outer = Object::Csg(Csg::new(CsgOperation::Union, Object::Csg(inner), far()));
+        outer.set_transform(Matrix::rotation_y(PI / 2.0));
+
+        let xs = Ray::new(Point::new(0.0, 0.0, -20.0), vector::Z).intersect(&outer);
+        let hit = &xs[0].object;
+        assert!(hit.same_object(&sphere));
+        assert_eq!(
+            hit.world_to_object(Point::new(-2.0, 0.0, -10.0)),
+            Point::new(0.0, 0.0, -1.0)
+        );
+
+        let third = 3_f64.sqrt() / 3.0;
+        assert_eq!(
+            hit.normal_to_world(Vector::new(third, third, third)),
+            Vector::new(third, third, -third)
+        );
+    }
 }
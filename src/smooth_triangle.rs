@@ -0,0 +1,267 @@
+use crate::shape::next_id;
+use crate::transformations::Transformable;
+use crate::utils::EPSILON;
+use crate::{BoundingBox, Intersection, Material, Matrix, Object, Point, Ray, Shape, Vector};
+
+#[derive(Debug, Clone)]
+pub struct SmoothTriangle {
+    transform: Matrix,
+    material: Material,
+    pub p1: Point,
+    pub p2: Point,
+    pub p3: Point,
+    pub n1: Vector,
+    pub n2: Vector,
+    pub n3: Vector,
+    pub(crate) id: usize,
+}
+
+impl SmoothTriangle {
+    #[must_use]
+    pub fn new(p1: Point, p2: Point, p3: Point, n1: Vector, n2: Vector, n3: Vector) -> Self {
+        Self {
+            transform: Matrix::default(),
+            material: Material::default(),
+            p1,
+            p2,
+            p3,
+            n1,
+            n2,
+            n3,
+            id: next_id(),
+        }
+    }
+
+    // A face of a mesh, sharing the mesh's id, transform and material.
+    pub(crate) fn mesh_face(
+        points: [Point; 3],
+        normals: [Vector; 3],
+        transform: Matrix,
+        material: &Material,
+        id: usize,
+    ) -> Self {
+        let [p1, p2, p3] = points;
+        let [n1, n2, n3] = normals;
+        Self {
+            transform,
+            material: material.clone(),
+            p1,
+            p2,
+            p3,
+            n1,
+            n2,
+            n3,
+            id,
+        }
+    }
+
+    fn edges(&self) -> (Vector, Vector) {
+        (self.p2 - self.p1, self.p3 - self.p1)
+    }
+
+    // Barycentric weights of `p2` and `p3` for a point on the triangle's plane.
+    fn barycentric(&self, point: Point) -> (f64, f64) {
+        let (e1, e2) = self.edges();
+        let to_point = point - self.p1;
+        let (d00, d01, d11) = (e1.dot(&e1), e1.dot(&e2), e2.dot(&e2));
+        let (d20, d21) = (to_point.dot(&e1), to_point.dot(&e2));
+        let denominator = d00 * d11 - d01 * d01;
+
+        (
+            (d11 * d20 - d01 * d21) / denominator,
+            (d00 * d21 - d01 * d20) / denominator,
+        )
+    }
+}
+
+impl Default for SmoothTriangle {
+    fn default() -> Self {
+        Self::new(
+            Point::new(0.0, 1.0, 0.0),
+            Point::new(-1.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Vector::new(0.0, 0.0, -1.0),
+            Vector::new(0.0, 0.0, -1.0),
+            Vector::new(0.0, 0.0, -1.0),
+        )
+    }
+}
+
+impl PartialEq for SmoothTriangle {
+    fn eq(&self, other: &Self) -> bool {
+        self.transform == other.transform
+            && self.material == other.material
+            && (self.p1, self.p2, self.p3) == (other.p1, other.p2, other.p3)
+            && (self.n1, self.n2, self.n3) == (other.n1, other.n2, other.n3)
+    }
+}
+
+impl Transformable for SmoothTriangle {
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transform = transform;
+    }
+}
+
+impl Shape for SmoothTriangle {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn get_material(&self) -> Material {
+        self.material.clone()
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection> {
+        let (e1, e2) = self.edges();
+        match intersect_face(self.p1, e1, e2, ray) {
+            Some((t, u, v)) => vec![Intersection::with_uv(
+                t,
+                &Object::SmoothTriangle(self.clone()),
+                u,
+                v,
+            )],
+            None => Vec::new(),
+        }
+    }
+
+    fn local_normal_at(&self, point: Point) -> Vector {
+        let (u, v) = self.barycentric(point);
+        self.local_normal_at_uv(point, u, v)
+    }
+
+    fn local_normal_at_uv(&self, _point: Point, u: f64, v: f64) -> Vector {
+        self.n2 * u + self.n3 * v + self.n1 * (1.0 - u - v)
+    }
+
+    fn local_face_normal_at(&self, _point: Point) -> Vector {
+        let (e1, e2) = self.edges();
+        e2.cross(&e1).normalize()
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::empty();
+        for point in [self.p1, self.p2, self.p3] {
+            bounds.add_point(point);
+        }
+        bounds
+    }
+}
+
+// Moller-Trumbore test against the triangle spanned by `e1` and `e2` from
+// `p1`, returning the hit's t and the barycentric (u, v).
+pub(crate) fn intersect_face(
+    p1: Point,
+    e1: Vector,
+    e2: Vector,
+    ray: &Ray,
+) -> Option<(f64, f64, f64)> {
+    let dir_cross_e2 = ray.direction.cross(&e2);
+    let det = e1.dot(&dir_cross_e2);
+    if det.abs() < EPSILON {
+        return None;
+    }
+
+    let f = 1.0 / det;
+    let p1_to_origin = ray.origin - p1;
+    let u = f * p1_to_origin.dot(&dir_cross_e2);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let origin_cross_e1 = p1_to_origin.cross(&e1);
+    let v = f * ray.direction.dot(&origin_cross_e1);
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    Some((f * e2.dot(&origin_cross_e1), u, v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::equal;
+    use crate::vector;
+
+    fn triangle() -> SmoothTriangle {
+        SmoothTriangle::new(
+            Point::new(0.0, 1.0, 0.0),
+            Point::new(-1.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(-1.0, 0.0, 0.0),
+            Vector::new(1.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn intersection_stores_uv() {
+        let r = Ray::new(Point::new(-0.2, 0.3, -2.0), vector::Z);
+        let xs = triangle().local_intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert!(equal(xs[0].t, 2.0));
+        let (u, v) = xs[0].uv.unwrap();
+        assert!(equal(u, 0.45));
+        assert!(equal(v, 0.25));
+    }
+
+    #[test]
+    fn ray_misses_triangle() {
+        let tri = triangle();
+        let cases = [
+            (Point::new(0.0, -1.0, -2.0), vector::Y),
+            (Point::new(1.0, 1.0, -2.0), vector::Z),
+            (Point::new(-1.0, 1.0, -2.0), vector::Z),
+            (Point::new(0.0, -1.0, -2.0), vector::Z),
+        ];
+
+        for (origin, direction) in cases {
+            assert!(tri.local_intersect(&Ray::new(origin, direction)).is_empty());
+        }
+    }
+
+    #[test]
+    fn interpolated_normals() {
+        let tri = Object::SmoothTriangle(triangle());
+        let i = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+
+        assert_eq!(
+            tri.normal_at_uv(Point::default(), i.uv),
+            Vector::new(-0.5547, 0.83205, 0.0)
+        );
+        assert_eq!(
+            tri.normal_at(Point::new(-0.2, 0.3, 0.0)),
+            Vector::new(-0.5547, 0.83205, 0.0)
+        );
+
+        let r = Ray::new(Point::new(-0.2, 0.3, -2.0), vector::Z);
+        let comps = i.prepare_computations(&r, std::slice::from_ref(&i));
+        assert_eq!(comps.normal, Vector::new(-0.5547, 0.83205, 0.0));
+    }
+
+    #[test]
+    fn flat_shading_uses_face_normal() {
+        let mut tri = triangle();
+        let mut material = tri.get_material();
+        material.smooth_normals = false;
+        tri.set_material(material);
+
+        assert_eq!(
+            tri.normal_at_uv(Point::default(), Some((0.45, 0.25))),
+            -vector::Z
+        );
+        assert_eq!(
+            tri.bounds(),
+            BoundingBox::new(Point::new(-1.0, 0.0, 0.0), Point::new(1.0, 1.0, 0.0))
+        );
+    }
+}
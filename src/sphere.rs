@@ -1,10 +1,14 @@
+use crate::shape::next_id;
 use crate::transformations::Transformable;
-use crate::{Intersection, Material, Matrix, Object, Point, Ray, Shape, Vector};
+use crate::utils::EPSILON;
+use crate::uv;
+use crate::{BoundingBox, Intersection, Material, Matrix, Object, Point, Ray, Shape, Vector};
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Sphere {
     pub transform: Matrix,
     pub material: Material,
+    pub(crate) id: usize,
 }
 
 impl Sphere {
@@ -15,6 +19,18 @@ impl Sphere {
         s.set_material(material);
         s
     }
+
+    #[must_use]
+    pub fn glass() -> Self {
+        Self {
+            material: Material {
+                transparency: 1.0,
+                refractive_index: 1.5,
+                ..Material::default()
+            },
+            ..Self::default()
+        }
+    }
 }
 
 impl Default for Sphere {
@@ -22,10 +38,17 @@ impl Default for Sphere {
         Sphere {
             transform: Matrix::eye(4),
             material: Material::default(),
+            id: next_id(),
         }
     }
 }
 
+impl PartialEq for Sphere {
+    fn eq(&self, other: &Self) -> bool {
+        self.transform == other.transform && self.material == other.material
+    }
+}
+
 impl Transformable for Sphere {
     fn get_transform(&self) -> Matrix {
         self.transform
@@ -37,8 +60,12 @@ impl Transformable for Sphere {
 }
 
 impl Shape for Sphere {
+    fn id(&self) -> usize {
+        self.id
+    }
+
     fn get_material(&self) -> Material {
-        self.material
+        self.material.clone()
     }
 
     fn set_material(&mut self, material: Material) {
@@ -53,17 +80,18 @@ impl Shape for Sphere {
 
         let discriminant = b * b - 4.0 * a * c;
 
-        if discriminant < 0.0 {
+        if discriminant / (4.0 * a * a) < -EPSILON {
             Vec::new()
         } else {
+            let discriminant = discriminant.max(0.0);
             vec![
                 Intersection::new(
                     (-b - discriminant.sqrt()) / (2.0 * a),
-                    &Object::Sphere(*self),
+                    &Object::Sphere(self.clone()),
                 ),
                 Intersection::new(
                     (-b + discriminant.sqrt()) / (2.0 * a),
-                    &Object::Sphere(*self),
+                    &Object::Sphere(self.clone()),
                 ),
             ]
         }
@@ -72,6 +100,14 @@ impl Shape for Sphere {
     fn local_normal_at(&self, point: Point) -> Vector {
         (point - Point::default()).normalize()
     }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0))
+    }
+
+    fn uv_at(&self, point: Point) -> Option<(f64, f64)> {
+        Some(uv::spherical_map(point))
+    }
 }
 
 #[cfg(test)]
@@ -108,6 +144,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn glass_sphere() {
+        let s = Sphere::glass();
+
+        assert_eq!(s.transform, Matrix::eye(4));
+        assert!(equal(s.material.transparency, 1.0));
+        assert!(equal(s.material.refractive_index, 1.5));
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(r.intersect(&Object::Sphere(s)).len(), 2);
+    }
+
     #[test]
     fn intersect_2_points() {
         let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
@@ -117,8 +165,9 @@ mod tests {
         assert_eq!(intersections.len(), 2);
         assert!(equal(intersections[0].t, 4.0));
         assert!(equal(intersections[1].t, 6.0));
-        assert_eq!(intersections[0].object, Object::Sphere(s));
-        assert_eq!(intersections[1].object, Object::Sphere(s));
+        let s = Object::Sphere(s);
+        assert_eq!(intersections[0].object, s);
+        assert_eq!(intersections[1].object, s);
     }
 
     #[test]
@@ -130,8 +179,29 @@ mod tests {
         assert_eq!(intersections.len(), 2);
         assert!(equal(intersections[0].t, 5.0));
         assert!(equal(intersections[1].t, 5.0));
-        assert_eq!(intersections[0].object, Object::Sphere(s));
-        assert_eq!(intersections[1].object, Object::Sphere(s));
+        let s = Object::Sphere(s);
+        assert_eq!(intersections[0].object, s);
+        assert_eq!(intersections[1].object, s);
+    }
+
+    #[test]
+    fn intersect_grazing_scaled_sphere() {
+        let r = Ray::new(
+            Point::new(0.0, 0.5 + 5e-8, -5.0),
+            Vector::new(0.0, 0.0, 1.0),
+        );
+        let s = Object::Sphere(Sphere::new(
+            Matrix::scaling(Vector::new(0.5, 0.5, 0.5)),
+            Material::default(),
+        ));
+        let intersections = r.intersect(&s);
+
+        assert_eq!(intersections.len(), 2);
+        assert!(equal(intersections[0].t, 5.0));
+        assert!(equal(intersections[1].t, 5.0));
+
+        let r = Ray::new(Point::new(0.0, 0.55, -5.0), Vector::new(0.0, 0.0, 1.0));
+        assert!(r.intersect(&s).is_empty());
     }
 
     #[test]
@@ -152,8 +222,9 @@ mod tests {
         assert_eq!(intersections.len(), 2);
         assert!(equal(intersections[0].t, -1.0));
         assert!(equal(intersections[1].t, 1.0));
-        assert_eq!(intersections[0].object, Object::Sphere(s));
-        assert_eq!(intersections[1].object, Object::Sphere(s));
+        let s = Object::Sphere(s);
+        assert_eq!(intersections[0].object, s);
+        assert_eq!(intersections[1].object, s);
     }
 
     #[test]
@@ -165,7 +236,8 @@ mod tests {
         assert_eq!(intersections.len(), 2);
         assert!(equal(intersections[0].t, -6.0));
         assert!(equal(intersections[1].t, -4.0));
-        assert_eq!(intersections[0].object, Object::Sphere(s));
-        assert_eq!(intersections[1].object, Object::Sphere(s));
+        let s = Object::Sphere(s);
+        assert_eq!(intersections[0].object, s);
+        assert_eq!(intersections[1].object, s);
     }
 }
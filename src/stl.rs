@@ -0,0 +1,214 @@
+use crate::{Mesh, Point, RaytracerError, Vector};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+const HEADER_LEN: usize = 80;
+const FACET_LEN: usize = 50;
+
+// Reads binary or ASCII STL data into a flat-shaded mesh. Binary files
+// may also start with "solid", so the size recorded in the header decides.
+pub fn read_stl(data: &[u8]) -> Result<Mesh, RaytracerError> {
+    let facets = if is_binary(data) {
+        read_binary(data)
+    } else {
+        read_ascii(data)?
+    };
+
+    // STL repeats every corner per facet; weld exact duplicates.
+    let mut vertices = Vec::new();
+    let mut lookup = HashMap::new();
+    let mut index_of = |point: Point| {
+        *lookup
+            .entry([point.x.to_bits(), point.y.to_bits(), point.z.to_bits()])
+            .or_insert_with(|| {
+                vertices.push(point);
+                vertices.len() - 1
+            })
+    };
+
+    let mut triangles = Vec::with_capacity(facets.len());
+    for (normal, [p1, mut p2, mut p3]) in facets {
+        let winding = (p2 - p1).cross(&(p3 - p1));
+        if winding.magnitude() == 0.0 {
+            continue;
+        }
+
+        // Faces are oriented by winding, but honor the stored normal when
+        // it disagrees; exporters often leave it zeroed.
+        if winding.dot(&normal) < 0.0 {
+            std::mem::swap(&mut p2, &mut p3);
+        }
+        triangles.push([index_of(p1), index_of(p2), index_of(p3)]);
+    }
+
+    Ok(Mesh::new(vertices, triangles))
+}
+
+type Facet = (Vector, [Point; 3]);
+
+fn is_binary(data: &[u8]) -> bool {
+    data.get(HEADER_LEN..HEADER_LEN + 4).is_some_and(|count| {
+        let count = u32::from_le_bytes([count[0], count[1], count[2], count[3]]);
+        usize::try_from(count).is_ok_and(|count| data.len() == HEADER_LEN + 4 + count * FACET_LEN)
+    })
+}
+
+fn read_binary(data: &[u8]) -> Vec<Facet> {
+    let float = |bytes: &[u8], index: usize| {
+        let start = index * 4;
+        f64::from(f32::from_le_bytes([
+            bytes[start],
+            bytes[start + 1],
+            bytes[start + 2],
+            bytes[start + 3],
+        ]))
+    };
+
+    data[HEADER_LEN + 4..]
+        .chunks_exact(FACET_LEN)
+        .map(|facet| {
+            let point = |index| {
+                Point::new(
+                    float(facet, index),
+                    float(facet, index + 1),
+                    float(facet, index + 2),
+                )
+            };
+            (
+                Vector::new(float(facet, 0), float(facet, 1), float(facet, 2)),
+                [point(3), point(6), point(9)],
+            )
+        })
+        .collect()
+}
+
+fn read_ascii(data: &[u8]) -> Result<Vec<Facet>, RaytracerError> {
+    let text = std::str::from_utf8(data).map_err(|_| RaytracerError::InvalidStl)?;
+    let mut tokens = text.split_whitespace();
+    if tokens.next() != Some("solid") {
+        return Err(RaytracerError::InvalidStl);
+    }
+
+    let triple = |tokens: &mut std::str::SplitWhitespace| {
+        let mut values = [0.0; 3];
+        for value in &mut values {
+            *value = tokens
+                .next()
+                .and_then(|token| token.parse().ok())
+                .ok_or(RaytracerError::InvalidStl)?;
+        }
+        Ok::<_, RaytracerError>((values[0], values[1], values[2]))
+    };
+
+    let mut facets = Vec::new();
+    let mut normal = Vector::default();
+    let mut vertices = Vec::with_capacity(3);
+    while let Some(token) = tokens.next() {
+        match token {
+            "facet" => {
+                if tokens.next() != Some("normal") {
+                    return Err(RaytracerError::InvalidStl);
+                }
+                let (x, y, z) = triple(&mut tokens)?;
+                normal = Vector::new(x, y, z);
+                vertices.clear();
+            }
+            "vertex" => {
+                let (x, y, z) = triple(&mut tokens)?;
+                vertices.push(Point::new(x, y, z));
+            }
+            "endfacet" => {
+                if vertices.len() != 3 {
+                    return Err(RaytracerError::InvalidStl);
+                }
+                facets.push((normal, [vertices[0], vertices[1], vertices[2]]));
+            }
+            "endsolid" => return Ok(facets),
+            _ => {}
+        }
+    }
+    Err(RaytracerError::InvalidStl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{vector, Object, Ray, Shape};
+
+    const ASCII: &str = "solid square
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 1 1 0
+    endloop
+  endfacet
+  facet normal 0 0 0
+    outer loop
+      vertex 0 0 0
+      vertex 1 1 0
+      vertex 0 1 0
+    endloop
+  endfacet
+endsolid square
+";
+
+    fn binary(facets: &[[f32; 12]]) -> Vec<u8> {
+        let mut data = b"solid but actually binary".to_vec();
+        data.resize(HEADER_LEN, 0);
+        data.extend(u32::try_from(facets.len()).unwrap().to_le_bytes());
+        for facet in facets {
+            for value in facet {
+                data.extend(value.to_le_bytes());
+            }
+            data.extend([0, 0]);
+        }
+        data
+    }
+
+    #[test]
+    fn ascii_stl() {
+        let mesh = read_stl(ASCII.as_bytes()).unwrap();
+        assert_eq!(mesh.faces(), [[0, 1, 2], [0, 1, 3]]);
+        assert_eq!(mesh.vertices().len(), 4);
+
+        // The first facet was flipped to face its stored -z normal.
+        assert_eq!(mesh.triangle(0).n1, -vector::Z);
+        let second = mesh.triangle(1);
+        assert_eq!(second.p3, Point::new(0.0, 1.0, 0.0));
+        assert_eq!(second.n1, vector::Z);
+
+        let r = Ray::new(Point::new(0.25, 0.75, -1.0), vector::Z);
+        assert_eq!(r.intersect(&Object::Mesh(mesh)).len(), 1);
+    }
+
+    #[test]
+    fn binary_stl() {
+        let data = binary(&[
+            [0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 2.0, 2.0, 0.0],
+            // Degenerate facets are dropped.
+            [0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0],
+        ]);
+        let mesh = read_stl(&data).unwrap();
+        assert_eq!(mesh.faces().len(), 1);
+
+        let triangle = mesh.triangle(0);
+        assert_eq!(triangle.p3, Point::new(2.0, 0.0, 0.0));
+        assert_eq!(triangle.normal_at(Point::new(1.5, 0.5, 0.0)), -vector::Z);
+    }
+
+    #[test]
+    fn malformed_stl() {
+        for data in [
+            &b"solid x facet normal 0 0 1 outer loop vertex 0 0 0 endloop endfacet endsolid"[..],
+            b"solid x facet normal 0 0 1",
+            b"not an stl",
+        ] {
+            assert!(matches!(read_stl(data), Err(RaytracerError::InvalidStl)));
+        }
+        assert!(matches!(
+            read_stl(&binary(&[[0.0; 12]])[..100]),
+            Err(RaytracerError::InvalidStl)
+        ));
+    }
+}
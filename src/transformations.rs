@@ -1,5 +1,8 @@
+use crate::utils::EPSILON;
 use crate::{Matrix, Point, Vector};
 
+use std::f64::consts::PI;
+
 pub trait Transformable {
     #[must_use]
     fn get_transform(&self) -> Matrix;
@@ -76,6 +79,38 @@ impl Matrix {
         Self::new(4, v_grid)
     }
 
+    #[must_use]
+    pub fn rotation_axis(axis: Vector, angle: f64) -> Self {
+        let Vector { x, y, z } = axis.normalize();
+        let (sin, cos) = angle.sin_cos();
+        let t = 1.0 - cos;
+
+        #[rustfmt::skip]
+        let v_grid = vec![
+            t * x * x + cos, t * x * y - sin * z, t * x * z + sin * y, 0.0,
+            t * x * y + sin * z, t * y * y + cos, t * y * z - sin * x, 0.0,
+            t * x * z - sin * y, t * y * z + sin * x, t * z * z + cos, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        ];
+
+        Self::new(4, v_grid)
+    }
+
+    #[must_use]
+    pub fn rotation_between(from: Vector, to: Vector) -> Self {
+        let (from, to) = (from.normalize(), to.normalize());
+        let cos = from.dot(&to).clamp(-1.0, 1.0);
+        let axis = from.cross(&to);
+
+        if axis.magnitude() > EPSILON {
+            Self::rotation_axis(axis, cos.acos())
+        } else if cos > 0.0 {
+            Self::default()
+        } else {
+            Self::rotation_axis(from.orthonormal_basis().0, PI)
+        }
+    }
+
     #[must_use]
     pub fn shearing(xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Self {
         #[rustfmt::skip]
@@ -107,6 +142,20 @@ impl Matrix {
 
         orientation * Matrix::translation(Vector::new(-from.x, -from.y, -from.z))
     }
+
+    #[must_use]
+    pub fn trs(translation: Vector, rotation: (f64, f64, f64), scale: Vector) -> Self {
+        Self::scaling(scale)
+            .then(Self::rotation_x(rotation.0))
+            .then(Self::rotation_y(rotation.1))
+            .then(Self::rotation_z(rotation.2))
+            .then(Self::translation(translation))
+    }
+
+    #[must_use]
+    pub fn then(self, other: Self) -> Self {
+        other * self
+    }
 }
 
 #[cfg(test)]
@@ -244,6 +293,45 @@ mod tests {
         assert_eq!(t2 * Point::new(0.0, 1.0, 0.0), Point::new(-1.0, 0.0, 0.0),);
     }
 
+    #[test]
+    fn rotation_axis() {
+        assert_eq!(
+            Matrix::rotation_axis(vector::X, PI / 3.0),
+            Matrix::rotation_x(PI / 3.0)
+        );
+        assert_eq!(
+            Matrix::rotation_axis(vector::Y * 2.0, -PI / 5.0),
+            Matrix::rotation_y(-PI / 5.0)
+        );
+        assert_eq!(
+            Matrix::rotation_axis(vector::Z, PI / 4.0),
+            Matrix::rotation_z(PI / 4.0)
+        );
+        assert_eq!(
+            Matrix::rotation_axis(Vector::new(1.0, 1.0, 1.0), 2.0 * PI / 3.0) * vector::X,
+            vector::Y
+        );
+    }
+
+    #[test]
+    fn rotation_between() {
+        let v = Vector::new(1.0, -2.0, 0.5);
+
+        assert_eq!(
+            Matrix::rotation_between(vector::X, vector::Y) * vector::X,
+            vector::Y
+        );
+        assert_eq!(Matrix::rotation_between(v, v * 3.0), Matrix::default());
+        assert_eq!(
+            Matrix::rotation_between(vector::X, -vector::X) * vector::X,
+            -vector::X
+        );
+        assert_eq!(
+            Matrix::rotation_between(v, vector::Z) * v.normalize(),
+            vector::Z
+        );
+    }
+
     #[test]
     fn shearing() {
         let t1 = Matrix::shearing(1.0, 0.0, 0.0, 0.0, 0.0, 0.0);
@@ -298,4 +386,37 @@ mod tests {
             ]),
         );
     }
+
+    #[test]
+    fn trs() {
+        let translation = Vector::new(10.0, 5.0, 7.0);
+        let scale = Vector::new(5.0, 5.0, 5.0);
+
+        assert_eq!(
+            Matrix::trs(translation, (PI / 2.0, PI / 4.0, PI / 3.0), scale),
+            Matrix::translation(translation)
+                * Matrix::rotation_z(PI / 3.0)
+                * Matrix::rotation_y(PI / 4.0)
+                * Matrix::rotation_x(PI / 2.0)
+                * Matrix::scaling(scale),
+        );
+
+        assert_eq!(
+            Matrix::trs(translation, (PI / 2.0, 0.0, 0.0), scale) * Point::new(1.0, 0.0, 1.0),
+            Point::new(15.0, 0.0, 7.0),
+        );
+    }
+
+    #[test]
+    fn then() {
+        let a = Matrix::rotation_x(PI / 2.0);
+        let b = Matrix::scaling(Vector::new(5.0, 5.0, 5.0));
+        let c = Matrix::translation(Vector::new(10.0, 5.0, 7.0));
+
+        assert_eq!(a.then(b), b * a);
+        assert_eq!(
+            a.then(b).then(c) * Point::new(1.0, 0.0, 1.0),
+            Point::new(15.0, 0.0, 7.0),
+        );
+    }
 }
@@ -0,0 +1,139 @@
+use crate::{Matrix, Point, Vector};
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+// Homogeneous coordinates shared by `Point` (w = 1) and `Vector` (w = 0), so
+// matrix products and arithmetic only need to be written once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Tuple {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+impl Tuple {
+    pub fn new(x: f64, y: f64, z: f64, w: f64) -> Self {
+        Self { x, y, z, w }
+    }
+}
+
+impl From<Point> for Tuple {
+    fn from(p: Point) -> Self {
+        Self::new(p.x, p.y, p.z, 1.0)
+    }
+}
+
+impl From<Vector> for Tuple {
+    fn from(v: Vector) -> Self {
+        Self::new(v.x, v.y, v.z, 0.0)
+    }
+}
+
+impl From<Tuple> for Point {
+    fn from(t: Tuple) -> Self {
+        debug_assert!(t.w != 0.0, "tuple {:?} is not a point", t);
+        Point::new(t.x, t.y, t.z)
+    }
+}
+
+impl From<Tuple> for Vector {
+    fn from(t: Tuple) -> Self {
+        // Infinite components turn w into 0 * inf = NaN, which is still a vector.
+        debug_assert!(t.w == 0.0 || t.w.is_nan(), "tuple {:?} is not a vector", t);
+        Vector::new(t.x, t.y, t.z)
+    }
+}
+
+impl Add for Tuple {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.x + other.x,
+            self.y + other.y,
+            self.z + other.z,
+            self.w + other.w,
+        )
+    }
+}
+
+impl Sub for Tuple {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(
+            self.x - other.x,
+            self.y - other.y,
+            self.z - other.z,
+            self.w - other.w,
+        )
+    }
+}
+
+impl Mul<f64> for Tuple {
+    type Output = Self;
+
+    fn mul(self, other: f64) -> Self {
+        Self::new(
+            self.x * other,
+            self.y * other,
+            self.z * other,
+            self.w * other,
+        )
+    }
+}
+
+impl Neg for Tuple {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self * -1.0
+    }
+}
+
+impl Mul<Tuple> for &Matrix {
+    type Output = Tuple;
+
+    fn mul(self, t: Tuple) -> Tuple {
+        let row = |r| {
+            self.get(r, 0) * t.x
+                + self.get(r, 1) * t.y
+                + self.get(r, 2) * t.z
+                + self.get(r, 3) * t.w
+        };
+        // Transforms are affine, so the bottom row is never read.
+        Tuple::new(row(0), row(1), row(2), t.w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector;
+
+    #[test]
+    fn w_component_selects_translation() {
+        let m = Matrix::translation(Vector::new(5.0, -3.0, 2.0));
+
+        let p = &m * Tuple::from(Point::new(-3.0, 4.0, 5.0));
+        assert_eq!(p, Tuple::new(2.0, 1.0, 7.0, 1.0));
+        assert_eq!(Point::from(p), Point::new(2.0, 1.0, 7.0));
+
+        let v = &m * Tuple::from(Vector::new(-3.0, 4.0, 5.0));
+        assert_eq!(v, Tuple::new(-3.0, 4.0, 5.0, 0.0));
+        assert_eq!(Vector::from(v), Vector::new(-3.0, 4.0, 5.0));
+
+        let diff = Tuple::from(Point::new(3.0, 2.0, 1.0)) - Tuple::from(Point::new(5.0, 6.0, 7.0));
+        assert_eq!(diff.w, 0.0);
+        assert_eq!(Vector::from(diff), Vector::new(-2.0, -4.0, -6.0));
+    }
+
+    #[test]
+    fn infinite_vector_stays_a_vector() {
+        assert!((Tuple::from(vector::X) * f64::INFINITY).w.is_nan());
+
+        let v = vector::X * f64::INFINITY;
+        assert!(v.x.is_infinite());
+    }
+}
@@ -1,6 +1,16 @@
-pub(crate) const EPSILON: f64 = 0.0001;
+pub const EPSILON: f64 = 0.0001;
 
 #[must_use]
-pub(crate) fn equal(a: f64, b: f64) -> bool {
-    (a - b).abs() < EPSILON
+pub fn equal(a: f64, b: f64) -> bool {
+    equal_within(a, b, EPSILON)
+}
+
+#[must_use]
+pub fn equal_within(a: f64, b: f64, epsilon: f64) -> bool {
+    (a - b).abs() < epsilon
+}
+
+#[must_use]
+pub fn equal_rel(a: f64, b: f64, abs_tol: f64, rel_tol: f64) -> bool {
+    (a - b).abs() <= abs_tol.max(rel_tol * a.abs().max(b.abs()))
 }
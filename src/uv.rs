@@ -0,0 +1,162 @@
+use crate::{Point, Vector};
+
+use std::f64::consts::PI;
+
+// Projections from object space onto the unit square, one per basic shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UvMapping {
+    Spherical,
+    Planar,
+    Cylindrical,
+    Cubic,
+}
+
+impl UvMapping {
+    #[must_use]
+    pub fn map(self, point: Point) -> (f64, f64) {
+        match self {
+            UvMapping::Spherical => spherical_map(point),
+            UvMapping::Planar => planar_map(point),
+            UvMapping::Cylindrical => cylindrical_map(point),
+            UvMapping::Cubic => cubic_map(point),
+        }
+    }
+}
+
+#[must_use]
+pub fn spherical_map(point: Point) -> (f64, f64) {
+    let theta = point.x.atan2(point.z);
+    let radius = (point - Point::default()).magnitude();
+    let phi = (point.y / radius).acos();
+    let raw_u = theta / (2.0 * PI);
+
+    (1.0 - (raw_u + 0.5), 1.0 - phi / PI)
+}
+
+// Tiles the xz-plane with unit squares.
+#[must_use]
+pub fn planar_map(point: Point) -> (f64, f64) {
+    (point.x.rem_euclid(1.0), point.z.rem_euclid(1.0))
+}
+
+// Wraps once around the y axis; v repeats every unit of height.
+#[must_use]
+pub fn cylindrical_map(point: Point) -> (f64, f64) {
+    let theta = point.x.atan2(point.z);
+    let raw_u = theta / (2.0 * PI);
+
+    (1.0 - (raw_u + 0.5), point.y.rem_euclid(1.0))
+}
+
+// Position on whichever face of the unit cube the point lies, with v up.
+#[must_use]
+pub fn cubic_map(point: Point) -> (f64, f64) {
+    let (_, u, v) = cube_face(point - Point::default());
+    (u, 1.0 - v)
+}
+
+// Faces are ordered +X, -X, +Y, -Y, +Z, -Z; u and v run from the top-left corner.
+#[must_use]
+pub fn cube_face(direction: Vector) -> (usize, f64, f64) {
+    let Vector { x, y, z } = direction;
+    let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+
+    let (face, sc, tc, ma) = if ax >= ay && ax >= az {
+        if x > 0.0 {
+            (0, -z, -y, ax)
+        } else {
+            (1, z, -y, ax)
+        }
+    } else if ay >= az {
+        if y > 0.0 {
+            (2, x, z, ay)
+        } else {
+            (3, x, -z, ay)
+        }
+    } else if z > 0.0 {
+        (4, x, -y, az)
+    } else {
+        (5, -x, -y, az)
+    };
+
+    (face, 0.5 * (sc / ma + 1.0), 0.5 * (tc / ma + 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::equal;
+
+    fn assert_uv((u, v): (f64, f64), expected: (f64, f64)) {
+        assert!(equal(u, expected.0) && equal(v, expected.1), "{}, {}", u, v);
+    }
+
+    #[test]
+    fn spherical_mapping() {
+        let cases = [
+            (Point::new(0.0, 0.0, -1.0), (0.0, 0.5)),
+            (Point::new(1.0, 0.0, 0.0), (0.25, 0.5)),
+            (Point::new(0.0, 0.0, 1.0), (0.5, 0.5)),
+            (Point::new(-1.0, 0.0, 0.0), (0.75, 0.5)),
+            (Point::new(0.0, 1.0, 0.0), (0.5, 1.0)),
+            (Point::new(0.0, -1.0, 0.0), (0.5, 0.0)),
+            (
+                Point::new(2.0_f64.sqrt() / 2.0, 2.0_f64.sqrt() / 2.0, 0.0),
+                (0.25, 0.75),
+            ),
+        ];
+        for (point, uv) in cases {
+            assert_uv(spherical_map(point), uv);
+        }
+    }
+
+    #[test]
+    fn planar_mapping() {
+        let cases = [
+            (Point::new(0.25, 0.0, 0.5), (0.25, 0.5)),
+            (Point::new(0.25, 0.0, -0.25), (0.25, 0.75)),
+            (Point::new(0.25, 0.5, -0.25), (0.25, 0.75)),
+            (Point::new(1.25, 0.0, 0.5), (0.25, 0.5)),
+            (Point::new(0.25, 0.0, -1.75), (0.25, 0.25)),
+            (Point::new(1.0, 0.0, -1.0), (0.0, 0.0)),
+        ];
+        for (point, uv) in cases {
+            assert_uv(planar_map(point), uv);
+        }
+    }
+
+    #[test]
+    fn cylindrical_mapping() {
+        let half = 2.0_f64.sqrt() / 2.0;
+        let cases = [
+            (Point::new(0.0, 0.0, -1.0), (0.0, 0.0)),
+            (Point::new(0.0, 0.5, -1.0), (0.0, 0.5)),
+            (Point::new(0.0, 1.0, -1.0), (0.0, 0.0)),
+            (Point::new(half, 0.5, -half), (0.125, 0.5)),
+            (Point::new(1.0, 0.5, 0.0), (0.25, 0.5)),
+            (Point::new(half, 0.5, half), (0.375, 0.5)),
+            (Point::new(0.0, -0.25, 1.0), (0.5, 0.75)),
+            (Point::new(-1.0, 1.25, 0.0), (0.75, 0.25)),
+        ];
+        for (point, uv) in cases {
+            assert_uv(cylindrical_map(point), uv);
+        }
+    }
+
+    #[test]
+    fn cubic_mapping() {
+        let cases = [
+            (Point::new(-0.5, 0.5, 1.0), (0.25, 0.75)),
+            (Point::new(0.5, -0.5, 1.0), (0.75, 0.25)),
+            (Point::new(0.5, 0.5, -1.0), (0.25, 0.75)),
+            (Point::new(-1.0, 0.5, -0.5), (0.25, 0.75)),
+            (Point::new(1.0, -0.5, -0.5), (0.75, 0.25)),
+            (Point::new(-0.5, 1.0, -0.5), (0.25, 0.75)),
+            (Point::new(-0.5, -1.0, 0.5), (0.25, 0.75)),
+        ];
+        for (point, uv) in cases {
+            assert_uv(cubic_map(point), uv);
+        }
+        assert_eq!(cube_face(Vector::new(0.1, -2.0, 0.3)).0, 3);
+    }
+}
@@ -1,5 +1,7 @@
-use crate::utils::equal;
+use crate::tuple::Tuple;
+use crate::utils::{equal_rel, equal_within, EPSILON};
 
+use std::fmt;
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
 #[derive(Debug, Clone, Copy)]
@@ -66,6 +68,40 @@ impl Vector {
     pub fn reflect(&self, normal: &Self) -> Self {
         *self - *normal * 2.0 * self.dot(normal)
     }
+
+    #[must_use]
+    pub fn refract(&self, normal: &Self, n_ratio: f64) -> Option<Self> {
+        let cos_i = -self.dot(normal);
+        let sin2_t = n_ratio * n_ratio * (1.0 - cos_i * cos_i);
+        if sin2_t > 1.0 {
+            return None;
+        }
+
+        let cos_t = (1.0 - sin2_t).sqrt();
+        Some(*self * n_ratio + *normal * (n_ratio * cos_i - cos_t))
+    }
+
+    #[must_use]
+    pub fn orthonormal_basis(&self) -> (Self, Self) {
+        let helper = if self.x.abs() > 0.9 { Y } else { X };
+        let u = self.cross(&helper).normalize();
+        let v = self.cross(&u);
+        (u, v)
+    }
+
+    #[must_use]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        equal_within(self.x, other.x, epsilon)
+            && equal_within(self.y, other.y, epsilon)
+            && equal_within(self.z, other.z, epsilon)
+    }
+
+    #[must_use]
+    pub fn approx_eq_rel(&self, other: &Self, abs_tol: f64, rel_tol: f64) -> bool {
+        equal_rel(self.x, other.x, abs_tol, rel_tol)
+            && equal_rel(self.y, other.y, abs_tol, rel_tol)
+            && equal_rel(self.z, other.z, abs_tol, rel_tol)
+    }
 }
 
 impl Default for Vector {
@@ -74,9 +110,15 @@ impl Default for Vector {
     }
 }
 
+impl fmt::Display for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<{}, {}, {}>", self.x, self.y, self.z)
+    }
+}
+
 impl PartialEq for Vector {
     fn eq(&self, other: &Self) -> bool {
-        equal(self.x, other.x) && equal(self.y, other.y) && equal(self.z, other.z)
+        self.approx_eq(other, EPSILON)
     }
 }
 
@@ -84,11 +126,7 @@ impl Add for Vector {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        Self {
-            x: self.x + other.x,
-            y: self.y + other.y,
-            z: self.z + other.z,
-        }
+        (Tuple::from(self) + Tuple::from(other)).into()
     }
 }
 
@@ -96,11 +134,7 @@ impl Sub for Vector {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        Self {
-            x: self.x - other.x,
-            y: self.y - other.y,
-            z: self.z - other.z,
-        }
+        (Tuple::from(self) - Tuple::from(other)).into()
     }
 }
 
@@ -108,11 +142,7 @@ impl Mul<f64> for Vector {
     type Output = Self;
 
     fn mul(self, other: f64) -> Self {
-        Self {
-            x: self.x * other,
-            y: self.y * other,
-            z: self.z * other,
-        }
+        (Tuple::from(self) * other).into()
     }
 }
 
@@ -132,17 +162,19 @@ impl Neg for Vector {
     type Output = Self;
 
     fn neg(self) -> Self {
-        Self {
-            x: -self.x,
-            y: -self.y,
-            z: -self.z,
-        }
+        (-Tuple::from(self)).into()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::equal;
+
+    #[test]
+    fn display_vector() {
+        assert_eq!(Vector::new(0.0, 1.5, -3.0).to_string(), "<0, 1.5, -3>");
+    }
 
     #[test]
     fn new_vector() {
@@ -245,4 +277,28 @@ mod tests {
             Vector::new(1.0, 0.0, 0.0),
         );
     }
+
+    #[test]
+    fn vector_refract() {
+        let normal = Vector::new(0.0, 1.0, 0.0);
+        let incoming = Vector::new(0.0, -1.0, 0.0);
+
+        assert_eq!(incoming.refract(&normal, 1.0 / 1.5), Some(incoming));
+
+        let oblique = Vector::new(1.0, -1.0, 0.0).normalize();
+        assert_eq!(oblique.refract(&normal, 1.0), Some(oblique));
+
+        let refracted = oblique.refract(&normal, 1.0 / 1.5).unwrap();
+        assert!(equal(refracted.magnitude(), 1.0));
+        assert!(equal(refracted.x, 2_f64.sqrt() / 2.0 / 1.5));
+        assert!(refracted.y < 0.0);
+    }
+
+    #[test]
+    fn vector_refract_total_internal_reflection() {
+        let normal = Vector::new(0.0, 1.0, 0.0);
+        let incoming = Vector::new(1.0, -1.0, 0.0).normalize();
+
+        assert_eq!(incoming.refract(&normal, 1.5), None);
+    }
 }
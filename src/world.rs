@@ -1,60 +1,643 @@
-use crate::{Color, Computations, Intersection, Object, Point, PointLight, Ray, Shape};
+use crate::pattern::CheckerPattern;
+use crate::rng::Rng;
+use crate::scene_hash::SceneHash;
+use crate::transformations::Transformable;
+use crate::uv::cube_face;
+use crate::{
+    point, vector, BoundingBox, Bvh, Camera, Canvas, Color, Computations, Intersection, Light,
+    Material, Matrix, Object, Pattern, Plane, Point, PointLight, Ray, Shape, Sphere, Vector,
+};
+
+use std::f64::consts::{FRAC_PI_4, PI};
+use std::sync::OnceLock;
+
+pub const RECURSION_DEPTH: usize = 5;
+const GLOSSY_SAMPLES: usize = 16;
+const GI_SEED: u64 = 0x5eed;
+const MIN_THROUGHPUT: f64 = 0.001;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fog {
+    pub color: Color,
+    pub density: f64,
+}
+
+impl Fog {
+    #[must_use]
+    pub fn new(color: Color, density: f64) -> Self {
+        Self { color, density }
+    }
+
+    #[must_use]
+    pub fn apply(&self, color: Color, distance: f64) -> Color {
+        let amount = 1.0 - (-self.density * distance).exp();
+        color + (self.color - color) * amount
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HitInfo {
+    pub object: Object,
+    pub point: Point,
+    pub t: f64,
+    pub normal: Vector,
+}
 
 #[derive(Debug, Clone, PartialEq)]
+pub enum Background {
+    Solid(Color),
+    Gradient { top: Color, bottom: Color },
+    CubeMap(Box<[Canvas; 6]>),
+}
+
+impl Background {
+    #[must_use]
+    pub fn color_at(&self, direction: Vector) -> Color {
+        match self {
+            Self::Solid(color) => *color,
+            Self::Gradient { top, bottom } => {
+                let t = 0.5 * (direction.normalize().y + 1.0);
+                bottom.lerp(*top, t)
+            }
+            Self::CubeMap(faces) => {
+                let (face, u, v) = cube_face(direction);
+                let canvas = &faces[face];
+
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    clippy::cast_precision_loss,
+                    clippy::cast_sign_loss
+                )]
+                let (x, y) = (
+                    ((u * canvas.width() as f64) as usize).min(canvas.width() - 1),
+                    ((v * canvas.height() as f64) as usize).min(canvas.height() - 1),
+                );
+                *canvas.pixel_at(x, y)
+            }
+        }
+    }
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Self::Solid(Color::black())
+    }
+}
+
+// Built on the first ray after a change, so adding objects one at a time
+// doesn't redo the work for each.
+#[derive(Debug, Clone)]
+struct Caches {
+    // Inverse of every sphere's transform, indexed like `World::objects`.
+    spheres: Vec<Option<Matrix>>,
+    bvh: Option<Bvh>,
+}
+
+#[derive(Debug, Clone)]
 pub struct World {
     pub objects: Vec<Object>,
-    pub light: PointLight,
+    pub lights: Vec<Light>,
+    pub fog: Option<Fog>,
+    pub background: Background,
+    pub reflection_background: Option<Background>,
+    // Bounces allowed for rays the camera sends into this world.
+    pub max_recursion_depth: usize,
+    use_bvh: bool,
+    caches: OnceLock<Caches>,
+    next_id: usize,
+}
+
+impl PartialEq for World {
+    fn eq(&self, other: &Self) -> bool {
+        self.objects == other.objects
+            && self.lights == other.lights
+            && self.fog == other.fog
+            && self.background == other.background
+            && self.reflection_background == other.reflection_background
+            && self.max_recursion_depth == other.max_recursion_depth
+    }
 }
 
 impl World {
     #[must_use]
-    pub fn new(objects: Vec<Object>, light: PointLight) -> Self {
-        Self { objects, light }
+    pub fn new(mut objects: Vec<Object>, light: impl Into<Light>) -> Self {
+        let mut next_id = 0;
+        for object in &mut objects {
+            object.assign_ids(next_id);
+            next_id += object.id_count();
+        }
+
+        Self {
+            objects,
+            lights: vec![light.into()],
+            fog: None,
+            background: Background::default(),
+            reflection_background: None,
+            max_recursion_depth: RECURSION_DEPTH,
+            use_bvh: false,
+            caches: OnceLock::new(),
+            next_id,
+        }
+    }
+
+    // Must be called after editing `objects` directly; the methods below do
+    // it for you.
+    pub fn prepare(&mut self) {
+        self.caches = OnceLock::new();
+    }
+
+    fn caches(&self) -> &Caches {
+        self.caches.get_or_init(|| Caches {
+            spheres: self
+                .objects
+                .iter()
+                .map(|object| match object {
+                    Object::Sphere(sphere) => Some(sphere.get_transform().inverse()),
+                    _ => None,
+                })
+                .collect(),
+            bvh: self.use_bvh.then(|| Bvh::build(&self.objects)),
+        })
+    }
+
+    // Objects lying entirely behind a ray's origin are skipped, so their
+    // negative-t hits no longer show up in `intersect`.
+    pub fn build_bvh(&mut self) {
+        self.use_bvh = true;
+        self.prepare();
+    }
+
+    // Visits the objects a ray may hit, stopping once `visit` returns true.
+    fn visit_candidates(&self, ray: &Ray, mut visit: impl FnMut(usize) -> bool) -> bool {
+        match &self.caches().bvh {
+            Some(bvh) => bvh.traverse(ray, &mut visit),
+            None => (0..self.objects.len()).any(visit),
+        }
+    }
+
+    // Objects get fresh ids on the way in, so copies of one shape stay
+    // distinct, e.g. when working out which objects a ray is inside.
+    fn adopt(&mut self, object: &mut Object) {
+        object.assign_ids(self.next_id);
+        self.next_id += object.id_count();
+    }
+
+    pub fn add_object(&mut self, mut object: Object) {
+        self.adopt(&mut object);
+        self.objects.push(object);
+        self.prepare();
+    }
+
+    // Applies `update` to the object at `index`, returning false if there is
+    // none. The object keeps its ids unless `update` changes its structure.
+    pub fn update_object(&mut self, index: usize, update: impl FnOnce(&mut Object)) -> bool {
+        let Some(object) = self.objects.get_mut(index) else {
+            return false;
+        };
+        let (id, count) = (object.id(), object.id_count());
+        update(object);
+        if object.id_count() == count {
+            object.assign_ids(id);
+        } else {
+            let mut object = std::mem::take(&mut self.objects[index]);
+            self.adopt(&mut object);
+            self.objects[index] = object;
+        }
+        self.prepare();
+        true
+    }
+
+    pub fn remove_object(&mut self, index: usize) -> Option<Object> {
+        if index >= self.objects.len() {
+            return None;
+        }
+
+        let removed = self.objects.remove(index);
+        self.prepare();
+        Some(removed)
+    }
+
+    pub fn replace_object(&mut self, index: usize, mut object: Object) -> Option<Object> {
+        if index >= self.objects.len() {
+            return None;
+        }
+
+        self.adopt(&mut object);
+        let slot = &mut self.objects[index];
+        let replaced = std::mem::replace(slot, object);
+        self.prepare();
+        Some(replaced)
+    }
+
+    pub fn remove_by_id(&mut self, id: usize) -> Option<Object> {
+        let index = self.objects.iter().position(|object| object.id() == id)?;
+        self.remove_object(index)
+    }
+
+    #[must_use]
+    pub fn demo_scene(h_size: usize, v_size: usize) -> (Self, Camera) {
+        let floor = Plane::new(
+            Matrix::default(),
+            Material {
+                pattern: Pattern::Checker(CheckerPattern::new(
+                    Color::new(0.9, 0.9, 0.9),
+                    Color::new(0.2, 0.2, 0.2),
+                )),
+                specular: 0.0,
+                reflective: 0.1,
+                ..Material::default()
+            },
+        );
+        let sphere = |translation: Vector, scale: f64, material: Material| {
+            Object::Sphere(Sphere::new(
+                Matrix::translation(translation)
+                    * Matrix::scaling(Vector::new(scale, scale, scale)),
+                material,
+            ))
+        };
+
+        let world = Self::new(
+            vec![
+                Object::Plane(floor),
+                sphere(
+                    Vector::new(-0.5, 1.0, 0.5),
+                    1.0,
+                    Material::plastic(Color::new(0.1, 1.0, 0.5)),
+                ),
+                sphere(
+                    Vector::new(1.5, 0.5, -0.5),
+                    0.5,
+                    Material::metal(Color::new(0.9, 0.6, 0.2)),
+                ),
+                sphere(
+                    Vector::new(-1.5, 0.33, -0.75),
+                    0.33,
+                    Material::matte(Color::new(1.0, 0.8, 0.1)),
+                ),
+            ],
+            PointLight::new(Point::new(-10.0, 10.0, -10.0), Color::white()),
+        );
+
+        let mut camera = Camera::new(h_size, v_size, PI / 3.0);
+        camera.transform = Matrix::view_transform(Point::new(0.0, 1.5, -5.0), point::UY, vector::Y);
+        (world, camera)
+    }
+
+    #[must_use]
+    pub fn scene_hash(&self) -> u64 {
+        self.quantized_hash()
+    }
+
+    #[must_use]
+    pub fn bounds(&self) -> BoundingBox {
+        self.objects
+            .iter()
+            .map(Shape::parent_space_bounds)
+            .filter(BoundingBox::is_finite)
+            .fold(BoundingBox::empty(), |bounds, b| bounds.merge(&b))
     }
 
     #[must_use]
     pub fn intersect(&self, ray: &Ray) -> Vec<Intersection> {
         let mut intersections = Vec::new();
+        self.intersect_into(ray, &mut intersections);
+        intersections
+    }
 
-        for object in &self.objects {
-            intersections.append(&mut ray.intersect(object));
-        }
+    pub fn intersect_into(&self, ray: &Ray, intersections: &mut Vec<Intersection>) {
+        intersections.clear();
+        self.visit_candidates(ray, |index| {
+            intersections.append(&mut self.intersect_object(index, ray));
+            false
+        });
 
         intersections.sort_unstable_by(|i, j| i.t.partial_cmp(&j.t).unwrap());
-        intersections
     }
 
+    #[allow(clippy::needless_pass_by_value)]
     #[must_use]
-    pub fn shade_hit(&self, comps: Computations) -> Color {
-        comps.object.get_material().lighting(
+    pub fn shade_hit(&self, comps: Computations, remaining: usize) -> Color {
+        let material = comps.object.get_material().filtered(
             &comps.object,
             comps.point,
-            self.light,
-            comps.eyev,
             comps.normal,
-            self.is_shadowed(comps.over_point),
-        )
+            comps.footprint,
+        );
+        // Area lights shade from each of their samples; ambient is shared out
+        // so it still counts once per light.
+        let (mut surface, mut highlight) = (Color::black(), Color::black());
+        for light in &self.lights {
+            let samples = light.samples_at(comps.over_point, self);
+            #[allow(clippy::cast_precision_loss)]
+            let ambient_share = 1.0 / samples.len() as f64;
+            for (light, share) in samples {
+                let attenuation = Color::white() * share;
+                let components = material.lighting_components(
+                    &comps.object,
+                    comps.point,
+                    light,
+                    comps.eyev,
+                    comps.normal,
+                    attenuation,
+                );
+                surface = surface
+                    + components.ambient * ambient_share
+                    + components.diffuse
+                    + components.specular;
+                if let Some(coat) = &material.coat {
+                    highlight = highlight
+                        + coat.specular(comps.point, light, comps.eyev, comps.normal, attenuation);
+                }
+            }
+        }
+        let reflected = self.reflected_color(&comps, remaining);
+        let refracted = self.refracted_color(&comps, remaining);
+        // Emission shows regardless of the lights.
+        let surface = surface + material.emissive;
+        let base = if material.reflective > 0.0 && material.transparency > 0.0 {
+            let reflectance = comps.schlick();
+            surface + reflected * reflectance + refracted * (1.0 - reflectance)
+        } else {
+            surface + reflected + refracted
+        };
+
+        let Some(coat) = material.coat else {
+            return base;
+        };
+        let weight = coat.weight(comps.eyev, comps.normal);
+        if weight <= 0.0 {
+            return base;
+        }
+
+        let mut coat_color = highlight;
+        let throughput = comps.throughput * weight;
+        if remaining > 0 && throughput.luminance() >= MIN_THROUGHPUT {
+            let ray = Ray::new(comps.over_point, comps.reflectv).with_throughput(throughput);
+            coat_color = coat_color + self.escape_color_at(&ray, remaining - 1);
+        }
+        base * (1.0 - weight) + coat_color * weight
     }
 
     #[must_use]
-    pub fn color_at(&self, ray: &Ray) -> Color {
-        let hit = Intersection::hit(&self.intersect(ray));
-        if hit.is_none() {
+    pub fn reflected_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let material = comps.object.get_material();
+        let throughput = comps.throughput * material.reflective;
+        if remaining == 0 || throughput.luminance() < MIN_THROUGHPUT {
+            return Color::black();
+        }
+
+        let reflectv = comps.reflectv;
+        if material.roughness <= 0.0 {
+            let ray = Ray::new(comps.over_point, reflectv).with_throughput(throughput);
+            return self.escape_color_at(&ray, remaining - 1) * material.reflective;
+        }
+
+        let spread = (material.roughness * FRAC_PI_4).tan();
+        let (u, v) = reflectv.orthonormal_basis();
+        let golden_angle = PI * (3.0 - 5_f64.sqrt());
+
+        // Fanning out at every bounce costs GLOSSY_SAMPLES^depth rays, so only
+        // the first bounce does; deeper ones follow a single jittered ray.
+        let count = if remaining >= self.max_recursion_depth {
+            GLOSSY_SAMPLES
+        } else {
+            1
+        };
+        #[allow(clippy::cast_precision_loss)]
+        let throughput = throughput * (1.0 / count as f64);
+        let point = comps.point;
+        let mut rng = Rng::new(
+            point.x.to_bits()
+                ^ point.y.to_bits().rotate_left(21)
+                ^ point.z.to_bits().rotate_left(42),
+        );
+
+        let mut total = Color::black();
+        let mut samples = 0;
+        for k in 0..count {
+            #[allow(clippy::cast_precision_loss)]
+            let (radius, angle) = if count == 1 {
+                (rng.next_f64().sqrt() * spread, 2.0 * PI * rng.next_f64())
+            } else {
+                (
+                    ((k as f64 + 0.5) / count as f64).sqrt() * spread,
+                    k as f64 * golden_angle,
+                )
+            };
+            let direction = (reflectv + (u * angle.cos() + v * angle.sin()) * radius).normalize();
+            if direction.dot(&comps.normal) <= 0.0 {
+                continue;
+            }
+
+            let ray = Ray::new(comps.over_point, direction).with_throughput(throughput);
+            total = total + self.escape_color_at(&ray, remaining - 1);
+            samples += 1;
+        }
+
+        if samples == 0 {
+            let ray = Ray::new(comps.over_point, reflectv).with_throughput(throughput);
+            return self.escape_color_at(&ray, remaining - 1) * material.reflective;
+        }
+
+        total * (material.reflective / f64::from(samples))
+    }
+
+    #[must_use]
+    pub fn refracted_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let material = comps.object.get_material();
+        let throughput = comps.throughput * material.transparency;
+        if remaining == 0 || throughput.luminance() < MIN_THROUGHPUT {
             return Color::black();
         }
+
+        let Some(direction) = (-comps.eyev).refract(&comps.normal, comps.n1 / comps.n2) else {
+            return Color::black();
+        };
+
+        let ray = Ray::new(comps.under_point, direction).with_throughput(throughput);
+        self.escape_color_at(&ray, remaining - 1) * material.transparency
+    }
+
+    #[must_use]
+    pub fn color_at(&self, ray: &Ray, remaining: usize) -> Color {
+        let intersections = self.intersect(ray);
+        self.shade_intersections(ray, &intersections, remaining, &self.background)
+    }
+
+    fn escape_color_at(&self, ray: &Ray, remaining: usize) -> Color {
+        let background = self
+            .reflection_background
+            .as_ref()
+            .unwrap_or(&self.background);
+        let intersections = self.intersect(ray);
+        self.shade_intersections(ray, &intersections, remaining, background)
+    }
+
+    #[must_use]
+    pub fn color_at_into(
+        &self,
+        ray: &Ray,
+        remaining: usize,
+        intersections: &mut Vec<Intersection>,
+    ) -> Color {
+        self.intersect_into(ray, intersections);
+        self.shade_intersections(ray, intersections, remaining, &self.background)
+    }
+
+    #[must_use]
+    pub fn color_at_batch(&self, rays: &[Ray], remaining: usize) -> Vec<Color> {
+        let mut intersections = Vec::new();
+        rays.iter()
+            .map(|ray| self.color_at_into(ray, remaining, &mut intersections))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn color_at_gi(&self, ray: &Ray, remaining: usize, samples: usize) -> Color {
+        self.color_at_gi_with(ray, remaining, samples, &mut Rng::new(GI_SEED))
+    }
+
+    #[must_use]
+    pub fn color_at_gi_with(
+        &self,
+        ray: &Ray,
+        remaining: usize,
+        samples: usize,
+        rng: &mut Rng,
+    ) -> Color {
+        let intersections = self.intersect(ray);
+        let Some(hit) = Intersection::hit(&intersections) else {
+            return self.shade_intersections(ray, &intersections, remaining, &self.background);
+        };
+        let comps = hit.prepare_computations(ray, &intersections);
+        let material = comps.object.get_material();
+        let direct = self.shade_hit(comps.clone(), remaining);
+        if remaining == 0 || samples == 0 || material.diffuse == 0.0 {
+            return direct;
+        }
+
+        let (u, v) = comps.normal.orthonormal_basis();
+        let mut gathered = Color::black();
+        for _ in 0..samples {
+            let angle = 2.0 * PI * rng.next_f64();
+            let radius = rng.next_f64().sqrt();
+            let height = (1.0 - radius * radius).max(0.0).sqrt();
+            let direction =
+                u * (radius * angle.cos()) + v * (radius * angle.sin()) + comps.normal * height;
+
+            let bounce = Ray::new(comps.over_point, direction.normalize());
+            gathered = gathered + self.color_at_gi_with(&bounce, remaining - 1, 1, rng);
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let indirect = gathered * (material.diffuse / samples as f64);
+        direct + indirect * material.surface_color(&comps.object, comps.point)
+    }
+
+    fn shade_intersections(
+        &self,
+        ray: &Ray,
+        intersections: &[Intersection],
+        remaining: usize,
+        background: &Background,
+    ) -> Color {
+        let hit = Intersection::hit(intersections);
+        if hit.is_none() {
+            return self
+                .fog
+                .map_or_else(|| background.color_at(ray.direction), |fog| fog.color);
+        }
         let hit = hit.unwrap();
-        let comps = hit.prepare_computations(ray);
-        self.shade_hit(comps)
+        let comps = hit.prepare_computations(ray, intersections);
+        let t = comps.t;
+        let color = self.shade_hit(comps, remaining);
+
+        match self.fog {
+            Some(fog) => fog.apply(color, t),
+            None => color,
+        }
+    }
+
+    #[must_use]
+    pub fn hit_info(&self, ray: &Ray) -> Option<HitInfo> {
+        let intersections = self.intersect(ray);
+        let comps = Intersection::hit(&intersections)?.prepare_computations(ray, &intersections);
+
+        Some(HitInfo {
+            object: comps.object,
+            point: comps.point,
+            t: comps.t,
+            normal: comps.normal,
+        })
     }
 
+    #[must_use]
+    pub fn lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    pub fn lights_at(&self, point: Point) -> impl Iterator<Item = (&Light, f64)> + '_ {
+        self.lights()
+            .iter()
+            .map(move |light| (light, light.intensity_at(point, self)))
+    }
+
+    // True when no light reaches the point.
     #[must_use]
     pub fn is_shadowed(&self, point: Point) -> bool {
-        let direction = self.light.position - point;
+        self.lights
+            .iter()
+            .all(|light| self.is_shadowed_by(point, &light.as_point()))
+    }
+
+    #[must_use]
+    pub fn is_shadowed_by(&self, point: Point, light: &PointLight) -> bool {
+        let direction = light.position - point;
         let distance = direction.magnitude();
         let ray = Ray::new(point, direction.normalize());
-        let hit = Intersection::hit(&self.intersect(&ray));
 
-        hit.map_or(false, |hit| hit.t <= distance)
+        self.intersect_any(&ray, distance)
+    }
+
+    fn intersect_object(&self, index: usize, ray: &Ray) -> Vec<Intersection> {
+        let object = &self.objects[index];
+        if let (Object::Sphere(sphere), Some(Some(inverse))) =
+            (object, self.caches().spheres.get(index))
+        {
+            return sphere.local_intersect(&ray.transform(inverse));
+        }
+        ray.intersect(object)
+    }
+
+    #[must_use]
+    pub fn intersect_any(&self, ray: &Ray, max_t: f64) -> bool {
+        self.visit_candidates(ray, |index| {
+            self.intersect_object(index, ray)
+                .iter()
+                .any(|i| i.t > 0.0 && i.t < max_t)
+        })
+    }
+
+    #[must_use]
+    pub fn shadow_attenuation(&self, point: Point, light: &PointLight) -> Color {
+        let direction = light.position - point;
+        let distance = direction.magnitude();
+        let ray = Ray::new(point, direction.normalize());
+
+        let mut attenuation = Color::white();
+        self.visit_candidates(&ray, |index| {
+            let occludes = self
+                .intersect_object(index, &ray)
+                .iter()
+                .any(|i| i.t > 0.0 && i.t <= distance);
+
+            if occludes {
+                attenuation = attenuation * self.objects[index].get_material().transparency;
+            }
+            attenuation == Color::black()
+        });
+
+        attenuation
     }
 }
 
@@ -66,8 +649,6 @@ impl Default for World {
 
 #[cfg(test)]
 pub(crate) mod test_world {
-    use crate::{Material, Matrix, Point, Sphere, Vector};
-
     use super::*;
 
     pub fn test_world() -> World {
@@ -94,14 +675,25 @@ pub(crate) mod test_world {
 mod tests {
     use super::test_world::test_world;
     use super::*;
-    use crate::{vector, Material, Matrix, Sphere};
+    use crate::light::test_light::{area_light, occluder};
+    use crate::pattern::StripePattern;
+    use crate::utils::{equal, EPSILON};
+    use crate::{AreaLight, Coat};
+
+    use std::f64::consts::PI;
+    use std::slice;
+
+    fn set_material(world: &mut World, index: usize, material: Material) {
+        assert!(world.update_object(index, |object| object.set_material(material)));
+    }
 
     #[test]
     fn new_world() {
         let world = World::default();
 
         assert!(world.objects.is_empty());
-        assert_eq!(world.light, PointLight::default());
+        assert_eq!(world.lights, [Light::Point(PointLight::default())]);
+        assert!(world.fog.is_none());
     }
 
     #[test]
@@ -112,6 +704,16 @@ mod tests {
         assert_eq!(world.objects[1].get_material(), Material::default());
     }
 
+    #[test]
+    fn demo_scene() {
+        let (world, camera) = World::demo_scene(20, 10);
+        assert_eq!(world.objects.len(), 4);
+        assert!(matches!(world.objects[0], Object::Plane(_)));
+
+        let image = camera.render(&world);
+        assert_ne!(*image.pixel_at(10, 5), Color::black());
+    }
+
     #[test]
     fn world_intersect() {
         let world = test_world();
@@ -125,28 +727,189 @@ mod tests {
         assert_eq!(intersections[3].t, 6.0);
     }
 
+    fn naive_ts(world: &World, ray: &Ray) -> Vec<f64> {
+        let mut xs: Vec<Intersection> = world
+            .objects
+            .iter()
+            .flat_map(|object| ray.intersect(object))
+            .collect();
+        xs.sort_unstable_by(|i, j| i.t.partial_cmp(&j.t).unwrap());
+        xs.iter().map(|i| i.t).collect()
+    }
+
+    fn ts(world: &World, ray: &Ray) -> Vec<f64> {
+        world.intersect(ray).iter().map(|i| i.t).collect()
+    }
+
+    #[test]
+    fn intersect_into_reuses_buffer() {
+        let world = test_world();
+        let rays = [
+            Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z),
+            Ray::new(Point::new(0.0, 0.5, -5.0), vector::Z),
+            Ray::new(Point::new(0.0, 5.0, -5.0), vector::Z),
+        ];
+
+        let mut buffer = Vec::new();
+        for ray in &rays {
+            world.intersect_into(ray, &mut buffer);
+            assert_eq!(buffer, world.intersect(ray));
+        }
+
+        let capacity = buffer.capacity();
+        for _ in 0..100 {
+            for ray in &rays {
+                world.intersect_into(ray, &mut buffer);
+            }
+        }
+        assert_eq!(buffer.capacity(), capacity);
+    }
+
+    #[test]
+    fn sphere_fast_path_matches() {
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
+        let world = test_world();
+        assert_eq!(ts(&world, &ray), naive_ts(&world, &ray));
+
+        let mut rng = Rng::new(7);
+        let mut random = |scale: f64| (rng.next_f64() - 0.5) * scale;
+        let mut objects = vec![Object::Plane(Plane::default())];
+        for _ in 0..20 {
+            let transform = Matrix::translation(Vector::new(random(6.0), random(6.0), random(6.0)))
+                * Matrix::scaling(Vector::new(
+                    0.5 + random(0.5),
+                    0.5 + random(0.5),
+                    0.5 + random(0.5),
+                ));
+            objects.push(Object::Sphere(Sphere::new(transform, Material::default())));
+        }
+        let mut world = World::new(objects, PointLight::default());
+        for _ in 0..50 {
+            let ray = Ray::new(
+                Point::new(random(2.0), random(2.0), -10.0),
+                Vector::new(random(0.5), random(0.5), 1.0).normalize(),
+            );
+            assert_eq!(ts(&world, &ray), naive_ts(&world, &ray));
+        }
+
+        world.update_object(1, |object| {
+            object.set_transform(Matrix::translation(Vector::new(0.0, 0.0, 2.0)));
+        });
+        world.add_object(Object::Sphere(Sphere::default()));
+        assert_eq!(ts(&world, &ray), naive_ts(&world, &ray));
+    }
+
+    #[test]
+    fn bvh_matches_linear_scan() {
+        let mut rng = Rng::new(11);
+        let mut random = |scale: f64| (rng.next_f64() - 0.5) * scale;
+        let mut objects = vec![Object::Plane(Plane::with_normal(
+            vector::Y,
+            Point::new(0.0, -20.0, 0.0),
+        ))];
+        for _ in 0..200 {
+            let transform =
+                Matrix::translation(Vector::new(random(40.0), random(40.0), random(40.0)))
+                    * Matrix::scaling(Vector::new(0.5, 0.5, 0.5));
+            objects.push(Object::Sphere(Sphere::new(transform, Material::default())));
+        }
+        let linear = World::new(objects, PointLight::default());
+        let mut world = linear.clone();
+        world.build_bvh();
+
+        for _ in 0..200 {
+            let ray = Ray::new(
+                Point::new(random(40.0), random(40.0), random(40.0)),
+                Vector::new(random(1.0), random(1.0), random(1.0)).normalize(),
+            );
+            // Objects entirely behind the origin are pruned along with their
+            // negative hits.
+            let ahead = |world: &World| -> Vec<f64> {
+                ts(world, &ray).into_iter().filter(|&t| t >= 0.0).collect()
+            };
+            assert_eq!(ahead(&world), ahead(&linear));
+            assert_eq!(
+                world.intersect_any(&ray, 10.0),
+                linear.intersect_any(&ray, 10.0)
+            );
+        }
+    }
+
+    #[test]
+    fn copied_objects_stay_distinct() {
+        let mut a = Object::Sphere(Sphere::glass());
+        a.set_transform(Matrix::scaling(Vector::new(2.0, 2.0, 2.0)));
+        let mut b = a.clone();
+        b.set_transform(Matrix::default());
+        b.set_material(Material {
+            refractive_index: 2.0,
+            ..b.get_material()
+        });
+
+        let world = World::new(vec![a, b], PointLight::default());
+        assert_ne!(world.objects[0].id(), world.objects[1].id());
+
+        let xs = world.intersect(&Ray::new(Point::new(0.0, 0.0, -4.0), vector::Z));
+        let expected = [(1.0, 1.5), (1.5, 2.0), (2.0, 1.5), (1.5, 1.0)];
+        assert_eq!(xs.len(), expected.len());
+        for (hit, (n1, n2)) in xs.iter().zip(expected) {
+            let (r1, r2) = crate::refraction_indices(hit, &xs);
+            assert!(equal(r1, n1) && equal(r2, n2));
+        }
+    }
+
+    #[test]
+    fn bvh_follows_object_changes() {
+        let mut world = World::new(
+            vec![Object::Sphere(Sphere::default())],
+            PointLight::default(),
+        );
+        world.build_bvh();
+        let ray = |x: f64| Ray::new(Point::new(x, 0.0, -5.0), vector::Z);
+
+        world.add_object(Object::Sphere(Sphere::new(
+            Matrix::translation(Vector::new(5.0, 0.0, 0.0)),
+            Material::default(),
+        )));
+        assert_eq!(world.intersect(&ray(5.0)).len(), 2);
+
+        assert!(world.update_object(1, |object| {
+            object.set_transform(Matrix::translation(Vector::new(10.0, 0.0, 0.0)));
+        }));
+        assert!(world.intersect(&ray(5.0)).is_empty());
+        assert_eq!(world.intersect(&ray(10.0)).len(), 2);
+        assert!(!world.update_object(2, |_| {}));
+
+        assert!(world.remove_object(0).is_some());
+        assert!(world.intersect(&ray(0.0)).is_empty());
+        assert_eq!(world.intersect(&ray(10.0)).len(), 2);
+    }
+
     #[test]
     fn shade_outside() {
         let world = test_world();
         let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
-        let s = world.objects[0];
-        let i = Intersection::new(4.0, &s);
-        let comps = i.prepare_computations(&ray);
+        let s = &world.objects[0];
+        let i = Intersection::new(4.0, s);
+        let comps = i.prepare_computations(&ray, slice::from_ref(&i));
 
-        assert_eq!(world.shade_hit(comps), Color::new(0.38066, 0.47583, 0.2855));
+        assert_eq!(
+            world.shade_hit(comps, RECURSION_DEPTH),
+            Color::new(0.38066, 0.47583, 0.2855)
+        );
     }
 
     #[test]
     fn shade_inside() {
         let mut world = test_world();
-        world.light = PointLight::new(Point::new(0.0, 0.25, 0.0), Color::white());
+        world.lights = vec![PointLight::new(Point::new(0.0, 0.25, 0.0), Color::white()).into()];
         let ray = Ray::new(Point::default(), vector::Z);
         let s = &world.objects[1];
         let i = Intersection::new(0.5, s);
-        let comps = i.prepare_computations(&ray);
+        let comps = i.prepare_computations(&ray, slice::from_ref(&i));
 
         assert_eq!(
-            world.shade_hit(comps),
+            world.shade_hit(comps, RECURSION_DEPTH),
             Color::new(0.90498, 0.90498, 0.90498)
         );
     }
@@ -156,7 +919,7 @@ mod tests {
         let world = test_world();
         let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Y);
 
-        assert_eq!(world.color_at(&ray), Color::black());
+        assert_eq!(world.color_at(&ray, RECURSION_DEPTH), Color::black());
     }
 
     #[test]
@@ -164,7 +927,37 @@ mod tests {
         let world = test_world();
         let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
 
-        assert_eq!(world.color_at(&ray), Color::new(0.38066, 0.47583, 0.2855));
+        assert_eq!(
+            world.color_at(&ray, RECURSION_DEPTH),
+            Color::new(0.38066, 0.47583, 0.2855)
+        );
+    }
+
+    #[test]
+    fn lights_accumulate() {
+        let mut world = test_world();
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
+        let single = world.color_at(&ray, RECURSION_DEPTH);
+
+        world.lights.push(world.lights[0]);
+        assert_eq!(world.color_at(&ray, RECURSION_DEPTH), single * 2.0);
+    }
+
+    #[test]
+    fn shadowed_only_when_every_light_is_blocked() {
+        let mut world = test_world();
+        let point = Point::new(10.0, -10.0, 10.0);
+        assert!(world.is_shadowed(point));
+
+        let fill = PointLight::new(Point::new(20.0, -10.0, 10.0), Color::white());
+        world.lights.push(fill.into());
+        assert!(!world.is_shadowed(point));
+
+        let lit: Vec<_> = world
+            .lights_at(point)
+            .map(|(_, intensity)| intensity)
+            .collect();
+        assert_eq!(lit, [0.0, 1.0]);
     }
 
     #[test]
@@ -172,16 +965,21 @@ mod tests {
         let mut world = test_world();
         let ray = Ray::new(Point::new(0.0, 0.0, 0.75), -vector::Z);
 
-        world.objects[0].set_material(Material {
-            ambient: 1.0,
-            ..Default::default()
-        });
-        world.objects[1].set_material(Material {
-            ambient: 1.0,
-            ..Default::default()
-        });
+        for index in 0..2 {
+            set_material(
+                &mut world,
+                index,
+                Material {
+                    ambient: 1.0,
+                    ..Default::default()
+                },
+            );
+        }
 
-        assert_eq!(world.color_at(&ray), world.objects[1].get_material().color);
+        assert_eq!(
+            world.color_at(&ray, RECURSION_DEPTH),
+            world.objects[1].get_material().color
+        );
     }
 
     #[test]
@@ -207,6 +1005,149 @@ mod tests {
         let world = test_world();
         assert!(!world.is_shadowed(Point::new(-2.0, 2.0, -2.0)));
     }
+
+    #[test]
+    fn remove_occluder() {
+        let floor = Object::Plane(Plane::with_normal(vector::Y, Point::new(0.0, -1.0, 0.0)));
+        let occluder = Object::Sphere(Sphere::default());
+        let mut world = World::new(
+            vec![floor, occluder],
+            PointLight::new(Point::new(0.0, 10.0, 0.0), Color::white()),
+        );
+        let id = world.objects[1].id();
+        let point = Point::new(0.0, -1.0, 0.0);
+        let ray = Ray::new(
+            Point::new(0.0, 1.0, -5.0),
+            (point - Point::new(0.0, 1.0, -5.0)).normalize(),
+        );
+        let shadowed = world.color_at(&ray, RECURSION_DEPTH);
+
+        assert!(world.is_shadowed(point));
+        assert!(world.remove_object(2).is_none());
+        assert_eq!(world.remove_by_id(id).map(|object| object.id()), Some(id));
+        assert_eq!(world.objects.len(), 1);
+        assert!(!world.is_shadowed(point));
+        assert!(world.color_at(&ray, RECURSION_DEPTH).r > shadowed.r);
+    }
+
+    #[test]
+    fn replace_object_material() {
+        let mut world = test_world();
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
+        let before = world.color_at(&ray, RECURSION_DEPTH);
+
+        let red = Object::Sphere(Sphere::new(
+            Matrix::default(),
+            Material::matte(Color::new(1.0, 0.0, 0.0)),
+        ));
+        let old = world.replace_object(0, red).unwrap();
+        let after = world.color_at(&ray, RECURSION_DEPTH);
+
+        assert_eq!(old, test_world().objects[0]);
+        assert_ne!(after, before);
+        assert!(equal(after.g, 0.0) && after.r > 0.0);
+        assert!(world.replace_object(5, old).is_none());
+    }
+
+    #[test]
+    fn lights_at_point() {
+        let mut world = test_world();
+        let lit: Vec<_> = world.lights_at(Point::new(-2.0, 2.0, -2.0)).collect();
+        assert_eq!(lit, [(&world.lights[0], 1.0)]);
+
+        let shadowed: Vec<_> = world.lights_at(Point::new(10.0, -10.0, 10.0)).collect();
+        assert_eq!(shadowed, [(&world.lights[0], 0.0)]);
+
+        for index in 0..world.objects.len() {
+            world.update_object(index, |object| {
+                let mut material = object.get_material();
+                material.transparency = 0.5;
+                object.set_material(material);
+            });
+        }
+        let (_, intensity) = world
+            .lights_at(Point::new(10.0, -10.0, 10.0))
+            .next()
+            .unwrap();
+        assert!(equal(intensity, 0.25));
+    }
+
+    #[test]
+    fn per_light_sample_count() {
+        let floor = Object::Plane(Plane::default());
+        let occluder = occluder(5.0, Material::default());
+        let point_world = World::new(
+            vec![floor.clone(), occluder.clone()],
+            PointLight::new(Point::new(0.0, 10.0, 0.0), Color::white()),
+        );
+        let single_world = World::new(vec![floor.clone(), occluder.clone()], area_light(1));
+        let soft_world = World::new(vec![floor, occluder], area_light(4));
+
+        for target in [Point::new(-0.2, 0.0, 0.0), Point::new(1.0, 0.0, 0.0)] {
+            let origin = Point::new(target.x, 1.0, -3.0);
+            let ray = Ray::new(origin, (target - origin).normalize());
+            assert_eq!(
+                single_world.color_at(&ray, RECURSION_DEPTH),
+                point_world.color_at(&ray, RECURSION_DEPTH)
+            );
+        }
+
+        let penumbra = Point::new(-0.2, EPSILON, 0.0);
+        let (_, hard) = point_world.lights_at(penumbra).next().unwrap();
+        let (light, soft) = soft_world.lights_at(penumbra).next().unwrap();
+        assert!(equal(hard, 1.0));
+        assert_eq!(light.samples(), 16);
+        assert!(soft > 0.0 && soft < 1.0);
+    }
+
+    #[test]
+    fn area_light_shades_from_its_samples() {
+        // The light's center sits on the floor's horizon, so only its upper
+        // half lights the floor.
+        let light = AreaLight::new(
+            Point::new(-1.0, -1.0, 5.0),
+            Vector::new(2.0, 0.0, 0.0),
+            2,
+            Vector::new(0.0, 2.0, 0.0),
+            2,
+            Color::white(),
+        );
+        let world = World::new(vec![Object::Plane(Plane::default())], light);
+        let ray = Ray::new(
+            Point::new(0.0, 1.0, -1.0),
+            Vector::new(0.0, -1.0, 1.0).normalize(),
+        );
+        let xs = world.intersect(&ray);
+        let comps = xs[0].prepare_computations(&ray, &xs);
+
+        let ambient = Material::default().ambient;
+        let color = world.shade_hit(comps, 0);
+        assert!(color.r > ambient + 0.01);
+    }
+
+    #[test]
+    fn intersect_any_matches_hit() {
+        let world = test_world();
+        let points = [
+            Point::new(0.0, 10.0, 0.0),
+            Point::new(10.0, -10.0, 10.0),
+            Point::new(-20.0, 20.0, -20.0),
+            Point::new(-2.0, 2.0, -2.0),
+        ];
+
+        for point in points {
+            let direction = world.lights[0].position() - point;
+            let distance = direction.magnitude();
+            let ray = Ray::new(point, direction.normalize());
+            let hit = Intersection::hit(&world.intersect(&ray));
+
+            assert_eq!(
+                world.intersect_any(&ray, distance),
+                hit.map_or(false, |hit| hit.t <= distance)
+            );
+        }
+    }
+
     #[test]
     fn shade_hit_and_shadows() {
         let light = PointLight::new(Point::new(0.0, 0.0, 10.0), Color::white());
@@ -218,8 +1159,584 @@ mod tests {
         let world = World::new(vec![s1, s2], light);
         let ray = Ray::new(Point::new(0.0, 0.0, 5.0), vector::Z);
         let i = Intersection::new(4.0, &world.objects[1]);
-        let comps = i.prepare_computations(&ray);
+        let comps = i.prepare_computations(&ray, slice::from_ref(&i));
+
+        assert_eq!(
+            world.shade_hit(comps, RECURSION_DEPTH),
+            Color::new(0.1, 0.1, 0.1)
+        );
+    }
+
+    #[test]
+    fn shadow_attenuation_opaque() {
+        let world = test_world();
+        let point = Point::new(10.0, -10.0, 10.0);
+
+        assert_eq!(
+            world.shadow_attenuation(point, &world.lights[0].as_point()),
+            Color::black()
+        );
+        assert_eq!(
+            world.shadow_attenuation(Point::new(0.0, 10.0, 0.0), &world.lights[0].as_point()),
+            Color::white()
+        );
+    }
+
+    #[test]
+    fn shadow_attenuation_transparent() {
+        let light = PointLight::new(Point::new(0.0, 10.0, 0.0), Color::white());
+        let floor = Object::Sphere(Sphere::new(
+            Matrix::translation(Vector::new(0.0, -1.0, 0.0)),
+            Material::default(),
+        ));
+        let glass = Object::Sphere(Sphere::new(
+            Matrix::translation(Vector::new(0.0, 5.0, 0.0)),
+            Material {
+                transparency: 1.0,
+                ..Default::default()
+            },
+        ));
+        let mut world = World::new(vec![floor, glass], light);
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
+        let i = Intersection::new(5.0, &world.objects[0]);
+        let unshadowed = world.shade_hit(
+            i.prepare_computations(&ray, slice::from_ref(&i)),
+            RECURSION_DEPTH,
+        );
+
+        assert_eq!(
+            world.shadow_attenuation(Point::default(), &world.lights[0].as_point()),
+            Color::white()
+        );
+
+        set_material(
+            &mut world,
+            1,
+            Material {
+                transparency: 0.5,
+                ..Default::default()
+            },
+        );
+        let ambient = Color::new(0.1, 0.1, 0.1);
+        let half = world.shade_hit(
+            i.prepare_computations(&ray, slice::from_ref(&i)),
+            RECURSION_DEPTH,
+        );
+
+        assert_eq!(
+            world.shadow_attenuation(Point::default(), &world.lights[0].as_point()),
+            Color::new(0.5, 0.5, 0.5)
+        );
+        assert_eq!(half - ambient, (unshadowed - ambient) * 0.5);
+    }
+
+    #[test]
+    fn fog_miss() {
+        let mut world = test_world();
+        world.fog = Some(Fog::new(Color::new(0.5, 0.5, 0.5), 0.1));
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Y);
+
+        assert_eq!(
+            world.color_at(&ray, RECURSION_DEPTH),
+            Color::new(0.5, 0.5, 0.5)
+        );
+    }
+
+    #[test]
+    fn fog_distance() {
+        let fog_color = Color::new(0.5, 0.5, 0.5);
+        let mut world = test_world();
+        world.fog = Some(Fog::new(fog_color, 0.01));
+
+        let near = Ray::new(Point::new(0.0, 0.0, -1.01), vector::Z);
+        let near_clear = Color::new(0.38066, 0.47583, 0.2855);
+        let near_color = world.color_at(&near, RECURSION_DEPTH);
+        assert!((near_color.r - near_clear.r).abs() < 0.001);
+        assert!((near_color.g - near_clear.g).abs() < 0.001);
+        assert!((near_color.b - near_clear.b).abs() < 0.001);
+
+        let far = Ray::new(Point::new(0.0, 0.0, -1000.0), vector::Z);
+        let far_color = world.color_at(&far, RECURSION_DEPTH);
+        assert!((far_color.r - fog_color.r).abs() < 0.001);
+        assert!((far_color.g - fog_color.g).abs() < 0.001);
+        assert!((far_color.b - fog_color.b).abs() < 0.001);
+    }
+
+    fn glossy_world(roughness: f64) -> World {
+        let mirror = Plane::new(
+            Matrix::default(),
+            Material {
+                reflective: 1.0,
+                roughness,
+                ambient: 0.0,
+                diffuse: 0.0,
+                specular: 0.0,
+                ..Default::default()
+            },
+        );
+        let wall = Plane::new(
+            Matrix::translation(vector::Z) * Matrix::rotation_x(PI / 2.0),
+            Material {
+                pattern: Pattern::Stripe(StripePattern::default()),
+                ambient: 1.0,
+                diffuse: 0.0,
+                specular: 0.0,
+                ..Default::default()
+            },
+        );
+        let light = PointLight::new(Point::new(0.0, 10.0, -10.0), Color::white());
+
+        World::new(vec![Object::Plane(mirror), Object::Plane(wall)], light)
+    }
+
+    #[test]
+    fn shade_hit_with_coat() {
+        let mut world = World {
+            lights: vec![PointLight::new(Point::new(0.0, 0.0, -10.0), Color::white()).into()],
+            ..World::default()
+        };
+        world.add_object(Object::Sphere(Sphere::default()));
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
+        let shade = |world: &World| {
+            let i = Intersection::new(4.0, &world.objects[0]);
+            world.shade_hit(
+                i.prepare_computations(&ray, slice::from_ref(&i)),
+                RECURSION_DEPTH,
+            )
+        };
+
+        let base = Material {
+            specular: 0.0,
+            ..Material::matte(Color::new(1.0, 0.0, 0.0))
+        };
+        set_material(&mut world, 0, base.clone());
+        let uncoated = shade(&world);
+
+        set_material(
+            &mut world,
+            0,
+            Material {
+                coat: Some(Coat {
+                    reflective: 0.0,
+                    ..Coat::default()
+                }),
+                ..base.clone()
+            },
+        );
+        assert_eq!(shade(&world), uncoated);
+
+        set_material(
+            &mut world,
+            0,
+            Material {
+                coat: Some(Coat::default()),
+                ..base
+            },
+        );
+        let coated = shade(&world);
+        assert!(equal(uncoated.g, 0.0));
+        assert!(coated.g > 0.03);
+        assert!(coated.r + coated.g + coated.b > uncoated.r + uncoated.g + uncoated.b);
+    }
+
+    #[test]
+    fn reflected_color_sharp() {
+        let world = glossy_world(0.0);
+        let ray = Ray::new(
+            Point::new(0.05, 1.0, -1.0),
+            Vector::new(0.0, -1.0, 1.0).normalize(),
+        );
+        let i = Intersection::new(2_f64.sqrt(), &world.objects[0]);
+        let comps = i.prepare_computations(&ray, slice::from_ref(&i));
+        let reflected = Ray::new(comps.over_point, Vector::new(0.0, 1.0, 1.0).normalize());
+
+        assert_eq!(
+            world.reflected_color(&comps, RECURSION_DEPTH),
+            world.color_at(&reflected, RECURSION_DEPTH - 1)
+        );
+        assert_eq!(
+            world.reflected_color(&comps, RECURSION_DEPTH),
+            Color::white()
+        );
+    }
+
+    #[test]
+    fn mutual_mirrors_respect_depth_limit() {
+        let mirror = |y: f64| {
+            let mut plane = Plane::with_normal(Vector::new(0.0, -y, 0.0), Point::new(0.0, y, 0.0));
+            let mut material = plane.get_material();
+            material.reflective = 1.0;
+            plane.set_material(material);
+            Object::Plane(plane)
+        };
+        let mut world = World::new(
+            vec![mirror(-1.0), mirror(1.0)],
+            PointLight::new(Point::default(), Color::white()),
+        );
+
+        let mut camera = Camera::new(1, 1, PI / 3.0);
+        camera.transform =
+            Matrix::view_transform(Point::default(), Point::new(0.0, 1.0, 1.0), vector::Z);
+        let bounced = *camera.render(&world).pixel_at(0, 0);
+        let hash = world.scene_hash();
+
+        world.max_recursion_depth = 0;
+        let direct = *camera.render(&world).pixel_at(0, 0);
+        assert!(direct.r < bounced.r);
+        assert_ne!(world.scene_hash(), hash);
+    }
+
+    #[test]
+    fn reflected_color_glossy() {
+        let world = glossy_world(0.5);
+        let ray = Ray::new(
+            Point::new(0.05, 1.0, -1.0),
+            Vector::new(0.0, -1.0, 1.0).normalize(),
+        );
+        let i = Intersection::new(2_f64.sqrt(), &world.objects[0]);
+        let color = world.reflected_color(
+            &i.prepare_computations(&ray, slice::from_ref(&i)),
+            RECURSION_DEPTH,
+        );
+
+        assert!(color.r > 0.1 && color.r < 0.9);
+        assert_eq!(color, Color::new(color.r, color.r, color.r));
+        assert_eq!(
+            world.reflected_color(&i.prepare_computations(&ray, slice::from_ref(&i)), 0),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn glossy_mirrors_stay_cheap() {
+        let mirror = |height| {
+            Object::Plane(Plane::new(
+                Matrix::translation(Vector::new(0.0, height, 0.0)),
+                Material {
+                    reflective: 0.9,
+                    roughness: 0.5,
+                    ..Default::default()
+                },
+            ))
+        };
+        let mut world = World::new(vec![mirror(0.0), mirror(2.0)], PointLight::default());
+        world.max_recursion_depth = 12;
+
+        // Fanning out at every level would trace 16^12 rays.
+        let start = std::time::Instant::now();
+        let ray = Ray::new(
+            Point::new(0.0, 1.0, 0.0),
+            Vector::new(0.0, -1.0, 0.3).normalize(),
+        );
+        let color = world.color_at(&ray, world.max_recursion_depth);
+        assert!(start.elapsed().as_secs() < 5);
+        assert!(!color.r.is_nan());
+    }
+
+    fn nested_shells(count: usize, transparency: f64) -> World {
+        let material = Material {
+            ambient: 0.0,
+            diffuse: 0.0,
+            specular: 0.0,
+            transparency,
+            ..Material::default()
+        };
+        let shells = (1..=count)
+            .map(|radius| {
+                #[allow(clippy::cast_precision_loss)]
+                let scale = radius as f64;
+                Object::Sphere(Sphere::new(
+                    Matrix::scaling(Vector::new(scale, scale, scale)),
+                    material.clone(),
+                ))
+            })
+            .collect();
+
+        World {
+            background: Background::Solid(Color::new(1e4, 1e4, 1e4)),
+            ..World::new(shells, PointLight::default())
+        }
+    }
+
+    #[test]
+    fn throughput_ends_weak_paths() {
+        let ray = Ray::new(Point::new(0.0, 0.0, -10.0), vector::Z);
+
+        let color = nested_shells(2, 0.3).color_at(&ray, 10);
+        assert!(equal(color.r, 1e4 * 0.3_f64.powi(4)));
+        assert_eq!(nested_shells(3, 0.3).color_at(&ray, 10), Color::black());
+
+        let strong = nested_shells(3, 0.95);
+        assert!(equal(strong.color_at(&ray, 6).r, 1e4 * 0.95_f64.powi(6)));
+        assert_eq!(strong.color_at(&ray, 5), Color::black());
+    }
+
+    #[test]
+    fn emissive_shows_without_light() {
+        let glow = Color::new(0.2, 0.6, 0.1);
+        let mut world = World::new(
+            vec![Object::Sphere(Sphere::default())],
+            PointLight::new(Point::new(0.0, 0.0, -10.0), Color::black()),
+        );
+        let mut material = Material::matte(Color::white());
+        material.ambient = 0.0;
+        material.emissive = glow;
+        set_material(&mut world, 0, material);
+
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
+        assert_eq!(world.color_at(&ray, RECURSION_DEPTH), glow);
+    }
+
+    #[test]
+    fn shade_hit_with_schlick() {
+        let mut world = test_world();
+        let mut floor = Plane::new(
+            Matrix::translation(Vector::new(0.0, -1.0, 0.0)),
+            Material::default(),
+        );
+        let mut material = floor.get_material();
+        material.reflective = 0.5;
+        material.transparency = 0.5;
+        material.refractive_index = 1.5;
+        floor.set_material(material);
+        let floor = Object::Plane(floor);
+
+        let mut ball = Sphere::new(
+            Matrix::translation(Vector::new(0.0, -3.5, -0.5)),
+            Material::default(),
+        );
+        let mut material = ball.get_material();
+        material.color = Color::new(1.0, 0.0, 0.0);
+        material.ambient = 0.5;
+        ball.set_material(material);
+        world.add_object(floor.clone());
+        world.add_object(Object::Sphere(ball));
+
+        let half = 2_f64.sqrt() / 2.0;
+        let ray = Ray::new(Point::new(0.0, 0.0, -3.0), Vector::new(0.0, -half, half));
+        let xs = vec![Intersection::new(2_f64.sqrt(), &floor)];
+        let comps = xs[0].prepare_computations(&ray, &xs);
+
+        // Green and blue match the book; the ball is redder because the
+        // half-transparent floor only dims its light instead of shadowing it.
+        assert_eq!(
+            world.shade_hit(comps, 5),
+            Color::new(1.11500, 0.69643, 0.69243)
+        );
+    }
+
+    #[test]
+    fn refracted_color_opaque() {
+        let world = test_world();
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
+        let xs = vec![
+            Intersection::new(4.0, &world.objects[0]),
+            Intersection::new(6.0, &world.objects[0]),
+        ];
+        let comps = xs[0].prepare_computations(&ray, &xs);
+
+        assert_eq!(
+            world.refracted_color(&comps, RECURSION_DEPTH),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn refracted_color_total_internal_reflection() {
+        let mut world = test_world();
+        let mut material = world.objects[0].get_material();
+        material.transparency = 1.0;
+        material.refractive_index = 1.5;
+        set_material(&mut world, 0, material);
+
+        let ray = Ray::new(Point::new(0.0, 0.0, 2_f64.sqrt() / 2.0), vector::Y);
+        let xs = vec![
+            Intersection::new(-(2_f64.sqrt()) / 2.0, &world.objects[0]),
+            Intersection::new(2_f64.sqrt() / 2.0, &world.objects[0]),
+        ];
+        let comps = xs[1].prepare_computations(&ray, &xs);
+
+        assert_eq!(world.refracted_color(&comps, 5), Color::black());
+        assert_eq!(world.refracted_color(&comps, 0), Color::black());
+    }
+
+    #[test]
+    fn miss_returns_solid_background() {
+        let mut world = test_world();
+        world.background = Background::Solid(Color::new(0.2, 0.4, 0.8));
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Y);
+
+        assert_eq!(
+            world.color_at(&ray, RECURSION_DEPTH),
+            Color::new(0.2, 0.4, 0.8)
+        );
+    }
+
+    #[test]
+    fn miss_returns_gradient_background() {
+        let mut world = test_world();
+        world.background = Background::Gradient {
+            top: Color::new(0.5, 0.7, 1.0),
+            bottom: Color::white(),
+        };
+
+        let up = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Y);
+        let down = Ray::new(Point::new(0.0, 0.0, -5.0), -vector::Y);
+
+        assert_eq!(
+            world.color_at(&up, RECURSION_DEPTH),
+            Color::new(0.5, 0.7, 1.0)
+        );
+        assert_eq!(world.color_at(&down, RECURSION_DEPTH), Color::white());
+    }
+
+    #[test]
+    fn cube_map_background_faces() {
+        let colors = [
+            Color::new(1.0, 0.0, 0.0),
+            Color::new(0.0, 1.0, 0.0),
+            Color::new(0.0, 0.0, 1.0),
+            Color::new(1.0, 1.0, 0.0),
+            Color::new(0.0, 1.0, 1.0),
+            Color::new(1.0, 0.0, 1.0),
+        ];
+        let faces = colors.map(|color| {
+            let mut canvas = Canvas::new(2, 2);
+            for y in 0..2 {
+                for x in 0..2 {
+                    canvas.write_pixel(x, y, color);
+                }
+            }
+            canvas
+        });
+        let background = Background::CubeMap(Box::new(faces));
+
+        let directions = [
+            vector::X,
+            -vector::X,
+            vector::Y,
+            -vector::Y,
+            vector::Z,
+            -vector::Z,
+        ];
+        for (direction, color) in directions.iter().zip(colors) {
+            assert_eq!(background.color_at(*direction), color);
+        }
+    }
+
+    #[test]
+    fn hit_info_reports_first_object() {
+        let world = test_world();
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z);
+        let info = world.hit_info(&ray).unwrap();
+
+        assert_eq!(info.object, world.objects[0]);
+        assert!(equal(info.t, 4.0));
+        assert_eq!(info.point, Point::new(0.0, 0.0, -1.0));
+        assert_eq!(info.normal, Vector::new(0.0, 0.0, -1.0));
+
+        let miss = Ray::new(Point::new(0.0, 0.0, -5.0), vector::Y);
+        assert_eq!(world.hit_info(&miss), None);
+    }
+
+    #[test]
+    fn color_at_batch_matches_color_at() {
+        let world = test_world();
+        let rays = [
+            Ray::new(Point::new(0.0, 0.0, -5.0), vector::Z),
+            Ray::new(Point::new(0.0, 0.0, -5.0), vector::Y),
+            Ray::new(Point::new(0.0, 0.0, 0.75), -vector::Z),
+            Ray::new(
+                Point::new(0.0, 0.5, -5.0),
+                Vector::new(0.1, -0.1, 1.0).normalize(),
+            ),
+        ];
+
+        let expected: Vec<Color> = rays
+            .iter()
+            .map(|ray| world.color_at(ray, RECURSION_DEPTH))
+            .collect();
+
+        assert_eq!(world.color_at_batch(&rays, RECURSION_DEPTH), expected);
+    }
+
+    #[test]
+    fn world_bounds_skip_infinite_objects() {
+        let s1 = Object::Sphere(Sphere::new(
+            Matrix::translation(Vector::new(-3.0, 0.0, 0.0)),
+            Material::default(),
+        ));
+        let s2 = Object::Sphere(Sphere::new(
+            Matrix::translation(Vector::new(3.0, 1.0, 0.0)),
+            Material::default(),
+        ));
+        let floor = Object::Plane(Plane::default());
+        let world = World::new(vec![s1, floor, s2], PointLight::default());
+
+        assert_eq!(
+            world.bounds(),
+            BoundingBox::new(Point::new(-4.0, -1.0, -1.0), Point::new(4.0, 2.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn gi_bounces_light_from_emissive_ceiling() {
+        let floor = Object::Plane(Plane::default());
+        let ceiling = Object::Plane(Plane::new(
+            Matrix::translation(Vector::new(0.0, 2.0, 0.0)),
+            Material {
+                ambient: 0.0,
+                diffuse: 0.0,
+                specular: 0.0,
+                emissive: Color::white(),
+                ..Default::default()
+            },
+        ));
+        let light = PointLight::new(Point::new(0.0, 1.0, 0.0), Color::black());
+        let world = World::new(vec![floor, ceiling], light);
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), -vector::Y);
+
+        assert_eq!(world.color_at(&ray, RECURSION_DEPTH), Color::black());
+
+        let color = world.color_at_gi(&ray, RECURSION_DEPTH, 16);
+        assert!(color.r > 0.5);
+        assert_eq!(color, Color::new(color.r, color.r, color.r));
+        assert_eq!(color, world.color_at_gi(&ray, RECURSION_DEPTH, 16));
+
+        assert_eq!(world.color_at_gi(&ray, 0, 16), world.color_at(&ray, 0));
+    }
+
+    #[test]
+    fn reflection_background_only_for_secondary_rays() {
+        let mirror = Object::Plane(Plane::new(
+            Matrix::default(),
+            Material {
+                ambient: 0.0,
+                diffuse: 0.0,
+                specular: 0.0,
+                reflective: 1.0,
+                ..Default::default()
+            },
+        ));
+        let mut world = World::new(vec![mirror], PointLight::default());
+        world.reflection_background = Some(Background::Solid(Color::new(0.5, 0.5, 0.5)));
+
+        let primary_miss = Ray::new(Point::new(0.0, 1.0, 0.0), vector::Y);
+        assert_eq!(
+            world.color_at(&primary_miss, RECURSION_DEPTH),
+            Color::black()
+        );
+
+        let at_mirror = Ray::new(
+            Point::new(0.0, 1.0, -1.0),
+            Vector::new(0.0, -1.0, 1.0).normalize(),
+        );
+        assert_eq!(
+            world.color_at(&at_mirror, RECURSION_DEPTH),
+            Color::new(0.5, 0.5, 0.5)
+        );
 
-        assert_eq!(world.shade_hit(comps), Color::new(0.1, 0.1, 0.1));
+        world.reflection_background = None;
+        assert_eq!(world.color_at(&at_mirror, RECURSION_DEPTH), Color::black());
     }
 }